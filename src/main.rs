@@ -1,4 +1,5 @@
 use std::{env, error::Error};
+mod random;
 mod task_01;
 mod task_02;
 mod task_03;
@@ -22,6 +23,14 @@ mod task_20;
 mod task_21;
 mod task_22;
 
+// Arguments given after the task index, for tasks that offer additional commands
+pub fn task_args() -> Vec<String> {
+    env::args()
+        .skip_while(|arg| arg.parse::<usize>().is_err())
+        .skip(1)
+        .collect()
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
     let args: Vec<_> = env::args()
         .filter_map(|arg| arg.parse::<usize>().ok())
@@ -0,0 +1,20 @@
+// A linear congruential generator, so that generated benchmark and test inputs are reproducible
+pub struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    pub fn new(seed: u64) -> Lcg {
+        Lcg { state: seed }
+    }
+
+    // A value below the bound, taken from the upper bits as the lower ones repeat quickly
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+
+        (self.state >> 33) % bound
+    }
+}
@@ -1,4 +1,13 @@
-use std::{cmp, collections::HashMap, error::Error, fs};
+use std::{
+    cmp,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    error::Error,
+    fs,
+};
+
+mod bags;
+
+use bags::DrawingModel;
 
 type Drawing = HashMap<String, u32>;
 type Game = (u32, Vec<Drawing>);
@@ -102,7 +111,7 @@ fn second() -> Result<(), Box<dyn Error>> {
 
         let sum: u32 = games
             .map(|(_game_id, drawings)| -> u32 {
-                let upper_bounds = drawings.iter().fold(HashMap::new(), |a,b| maximum_drawing(&a, b));
+                let upper_bounds = minimum_bag(&drawings);
 
                 return drawing_power(&upper_bounds);
             })
@@ -114,7 +123,199 @@ fn second() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+// The smallest bag that could have produced all drawings of a game
+fn minimum_bag(drawings: &[Drawing]) -> Drawing {
+    return drawings
+        .iter()
+        .fold(HashMap::new(), |a, b| maximum_drawing(&a, b));
+}
+
+// All colors mentioned in any drawing of the given games
+fn colors(games: &[Game]) -> BTreeSet<String> {
+    return games
+        .iter()
+        .flat_map(|(_, drawings)| drawings.iter().flat_map(|drawing| drawing.keys().cloned()))
+        .collect();
+}
+
+fn possible_games(games: &[Game], limits: &Drawing) -> Vec<u32> {
+    return games
+        .iter()
+        .filter(|(_, drawings)| game_was_possible(drawings, limits))
+        .map(|(game_id, _)| *game_id)
+        .collect();
+}
+
+fn games_without_color(games: &[Game], color: &str) -> Vec<u32> {
+    return games
+        .iter()
+        .filter(|(_, drawings)| {
+            drawings
+                .iter()
+                .all(|drawing| drawing.get(color).copied().unwrap_or(0) == 0)
+        })
+        .map(|(game_id, _)| *game_id)
+        .collect();
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct ColorStatistics {
+    games: usize,
+    drawings: usize,
+    total: u32,
+    maximum: u32,
+}
+
+fn color_statistics(games: &[Game]) -> BTreeMap<String, ColorStatistics> {
+    let mut statistics: BTreeMap<String, ColorStatistics> = colors(games)
+        .into_iter()
+        .map(|color| (color, ColorStatistics::default()))
+        .collect();
+
+    for (_, drawings) in games {
+        for (color, count) in minimum_bag(drawings) {
+            let entry = statistics.entry(color).or_default();
+            entry.games += 1;
+            entry.maximum = cmp::max(entry.maximum, count);
+        }
+
+        for (color, count) in drawings.iter().flatten() {
+            let entry = statistics.entry(color.clone()).or_default();
+            entry.drawings += 1;
+            entry.total += count;
+        }
+    }
+
+    return statistics;
+}
+
+// Parses limits like "red=12" given on the command line
+fn parse_limits(args: &[String]) -> Option<Drawing> {
+    return args
+        .iter()
+        .map(|arg| -> Option<(String, u32)> {
+            let (color, count) = arg.split_once('=')?;
+
+            Some((color.to_string(), count.parse::<u32>().ok()?))
+        })
+        .collect();
+}
+
+fn parse_model(arg: &str) -> Option<DrawingModel> {
+    return match arg {
+        "with" => Some(DrawingModel::WithReplacement),
+        "without" => Some(DrawingModel::WithoutReplacement),
+        _ => None,
+    };
+}
+
+fn query(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let paths = ["./inputs/02/example-1.txt", "./inputs/02/input.txt"];
+    let usage = "Usage: 2 possible <color>=<count>... | minimum | missing <color> | stats \
+        | estimate <with|without> <bag size> | plausibility <with|without> <color>=<count>...";
+
+    for path in paths {
+        println!("Reading file {}", path);
+        let file = fs::read_to_string(path)?;
+
+        let games = file.lines().filter_map(parse_game).collect::<Vec<_>>();
+
+        match args.split_first() {
+            Some((command, limits)) if command == "possible" => {
+                let limits = parse_limits(limits).ok_or(usage)?;
+                let game_ids = possible_games(&games, &limits);
+
+                println!("Possible games: {:?}", game_ids);
+                println!("Sum is {}", game_ids.iter().sum::<u32>());
+            }
+            Some((command, [])) if command == "minimum" => {
+                for (game_id, drawings) in games.iter() {
+                    let bag = minimum_bag(drawings);
+                    let bag = bag.iter().collect::<BTreeMap<_, _>>();
+
+                    println!(
+                        "Game {}: {:?}, power {}",
+                        game_id,
+                        bag,
+                        bag.values().copied().product::<u32>()
+                    );
+                }
+            }
+            Some((command, [color])) if command == "missing" => {
+                println!(
+                    "Games without {}: {:?}",
+                    color,
+                    games_without_color(&games, color)
+                );
+            }
+            Some((command, [])) if command == "stats" => {
+                for (color, statistics) in color_statistics(&games) {
+                    println!(
+                        "{}: seen in {} games and {} drawings, {} cubes drawn in total, at most {} in a game",
+                        color, statistics.games, statistics.drawings, statistics.total, statistics.maximum
+                    );
+                }
+            }
+            Some((command, [model, size])) if command == "estimate" => {
+                let model = parse_model(model).ok_or(usage)?;
+                let size = size.parse::<u32>()?;
+
+                for (game_id, drawings) in games.iter() {
+                    match bags::estimate_bag(drawings, size, model) {
+                        None => println!("Game {}: impossible with {} cubes", game_id, size),
+                        Some(estimate) => {
+                            let colors = estimate
+                                .bag
+                                .iter()
+                                .map(|(color, count)| {
+                                    let (lower, upper) = estimate.intervals[color];
+
+                                    format!("{} {} ({}..={})", count, color, lower, upper)
+                                })
+                                .collect::<Vec<_>>();
+
+                            println!(
+                                "Game {}: {}, log likelihood {:.3}",
+                                game_id,
+                                colors.join(", "),
+                                estimate.log_likelihood
+                            );
+                        }
+                    }
+                }
+            }
+            Some((command, bag)) if command == "plausibility" && !bag.is_empty() => {
+                let model = parse_model(&bag[0]).ok_or(usage)?;
+                let bag = parse_limits(&bag[1..]).ok_or(usage)?;
+                let size = bag.values().sum::<u32>();
+
+                for (game_id, drawings) in games.iter() {
+                    let best = bags::estimate_bag(drawings, size, model);
+
+                    match (bags::log_likelihood(&bag, drawings, model), best) {
+                        (Some(log_likelihood), Some(best)) => println!(
+                            "Game {}: log likelihood {:.3}, relative plausibility {:.4}",
+                            game_id,
+                            log_likelihood,
+                            (log_likelihood - best.log_likelihood).exp()
+                        ),
+                        _ => println!("Game {}: impossible", game_id),
+                    }
+                }
+            }
+            _ => return Err(usage.into()),
+        }
+    }
+
+    Ok(())
+}
+
 pub fn main() -> Result<(), Box<dyn Error>> {
+    let args = crate::task_args();
+    if !args.is_empty() {
+        return query(&args);
+    }
+
     println!("02-1:");
     first()?;
     println!("02-2:");
@@ -125,7 +326,24 @@ pub fn main() -> Result<(), Box<dyn Error>> {
 mod tests {
     use std::collections::HashMap;
 
-    use crate::task_02::{parse_game, Drawing, Game};
+    use crate::task_02::{
+        color_statistics, games_without_color, minimum_bag, parse_game, possible_games, Drawing,
+        Game,
+    };
+
+    fn example_games() -> Vec<Game> {
+        [
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue",
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+            "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red",
+            "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green",
+            "Game 6: 2 green, 5 purple; 7 purple",
+        ]
+        .iter()
+        .filter_map(|line| parse_game(line))
+        .collect()
+    }
 
     #[test]
     fn parse_game_parses_first_example_line() {
@@ -146,4 +364,57 @@ mod tests {
 
         assert_eq!(game, expected);
     }
+
+    #[test]
+    fn possible_games_respects_arbitrary_limits() {
+        let games = example_games();
+        let limits: Drawing = HashMap::from([
+            (String::from("red"), 12),
+            (String::from("green"), 13),
+            (String::from("blue"), 14),
+        ]);
+
+        assert_eq!(possible_games(&games, &limits), Vec::from([1, 2, 5]));
+
+        let limits: Drawing =
+            HashMap::from([(String::from("green"), 2), (String::from("purple"), 7)]);
+
+        assert_eq!(possible_games(&games, &limits), Vec::from([6]));
+    }
+
+    #[test]
+    fn minimum_bag_matches_first_example_game() {
+        let (_, drawings) = &example_games()[0];
+
+        let expected: Drawing = HashMap::from([
+            (String::from("red"), 4),
+            (String::from("green"), 2),
+            (String::from("blue"), 6),
+        ]);
+
+        assert_eq!(minimum_bag(drawings), expected);
+    }
+
+    #[test]
+    fn games_without_color_finds_new_colors() {
+        let games = example_games();
+
+        assert_eq!(
+            games_without_color(&games, "purple"),
+            Vec::from([1, 2, 3, 4, 5])
+        );
+        assert_eq!(games_without_color(&games, "red"), Vec::from([6]));
+    }
+
+    #[test]
+    fn color_statistics_counts_drawings_and_games() {
+        let statistics = color_statistics(&example_games());
+        let purple = statistics.get("purple").unwrap();
+
+        assert_eq!(statistics.len(), 4);
+        assert_eq!(purple.games, 1);
+        assert_eq!(purple.drawings, 2);
+        assert_eq!(purple.total, 12);
+        assert_eq!(purple.maximum, 7);
+    }
 }
@@ -0,0 +1,295 @@
+use std::collections::BTreeMap;
+
+use super::Drawing;
+
+// Log likelihood ratio bound for a 95% confidence interval (half the χ² quantile with one degree of freedom)
+const CONFIDENCE_THRESHOLD: f64 = 1.920729;
+
+// How cubes are taken out of the bag within a single drawing.
+// Between drawings the cubes are always put back.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DrawingModel {
+    WithReplacement,
+    WithoutReplacement,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BagEstimate {
+    pub bag: BTreeMap<String, u32>,
+    pub log_likelihood: f64,
+    pub intervals: BTreeMap<String, (u32, u32)>,
+}
+
+fn log_binomial(n: u32, k: u32) -> f64 {
+    if k > n {
+        return f64::NEG_INFINITY;
+    }
+
+    return (0..k)
+        .map(|i| f64::from(n - i).ln() - f64::from(i + 1).ln())
+        .sum();
+}
+
+// Summary of the observations of a single color across all drawings
+struct Observation {
+    total: u32,
+    maximum: u32,
+    counts: Vec<u32>,
+}
+
+fn observe(drawings: &[Drawing]) -> BTreeMap<String, Observation> {
+    let mut observations: BTreeMap<String, Observation> = BTreeMap::new();
+
+    for (color, count) in drawings.iter().flatten() {
+        let observation = observations.entry(color.clone()).or_insert(Observation {
+            total: 0,
+            maximum: 0,
+            counts: Vec::new(),
+        });
+
+        observation.total += count;
+        observation.maximum = observation.maximum.max(*count);
+        observation.counts.push(*count);
+    }
+
+    return observations;
+}
+
+// The part of the log likelihood that depends on the count of a single color.
+// Terms that only depend on the bag size are left out, as they are shared by all bags of that size.
+fn color_log_likelihood(observation: &Observation, count: u32, model: DrawingModel) -> f64 {
+    return match model {
+        DrawingModel::WithReplacement => {
+            if observation.total == 0 {
+                0.0
+            } else if count == 0 {
+                f64::NEG_INFINITY
+            } else {
+                f64::from(observation.total) * f64::from(count).ln()
+            }
+        }
+        DrawingModel::WithoutReplacement => observation
+            .counts
+            .iter()
+            .map(|k| log_binomial(count, *k))
+            .sum(),
+    };
+}
+
+fn size_log_likelihood(drawings: &[Drawing], size: u32, model: DrawingModel) -> f64 {
+    return drawings
+        .iter()
+        .map(|drawing| {
+            let drawn = drawing.values().sum::<u32>();
+
+            return match model {
+                DrawingModel::WithReplacement => -f64::from(drawn) * f64::from(size).ln(),
+                DrawingModel::WithoutReplacement => -log_binomial(size, drawn),
+            };
+        })
+        .sum();
+}
+
+fn minimum_count(observation: &Observation, model: DrawingModel) -> u32 {
+    return match model {
+        DrawingModel::WithReplacement => observation.total.min(1),
+        DrawingModel::WithoutReplacement => observation.maximum,
+    };
+}
+
+// Distributes cubes over the colors maximizing the likelihood.
+// The likelihood is a sum of concave terms per color, so handing out cubes one by one
+// to the color that gains the most yields the optimum.
+fn distribute(
+    observations: &BTreeMap<String, &Observation>,
+    size: u32,
+    model: DrawingModel,
+) -> Option<BTreeMap<String, u32>> {
+    let mut bag: BTreeMap<String, u32> = observations
+        .iter()
+        .map(|(color, observation)| (color.clone(), minimum_count(observation, model)))
+        .collect();
+
+    let mut remaining = size.checked_sub(bag.values().sum())?;
+
+    while remaining > 0 {
+        let (color, _) = observations
+            .iter()
+            .map(|(color, observation)| {
+                let count = bag[color];
+                let gain = color_log_likelihood(observation, count + 1, model)
+                    - color_log_likelihood(observation, count, model);
+
+                return (color, gain);
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+
+        *bag.get_mut(color)? += 1;
+        remaining -= 1;
+    }
+
+    return Some(bag);
+}
+
+// Log likelihood of a bag producing the given drawings, or None if the bag could not have produced them
+pub fn log_likelihood(bag: &Drawing, drawings: &[Drawing], model: DrawingModel) -> Option<f64> {
+    let size = bag.values().sum::<u32>();
+
+    let color_part = observe(drawings)
+        .iter()
+        .map(|(color, observation)| {
+            return color_log_likelihood(observation, bag.get(color).copied().unwrap_or(0), model);
+        })
+        .sum::<f64>();
+    let constant_part = match model {
+        // The multinomial coefficients do not depend on the bag but make likelihoods comparable across models
+        DrawingModel::WithReplacement => drawings
+            .iter()
+            .map(|drawing| {
+                let mut drawn = 0;
+
+                return drawing
+                    .values()
+                    .map(|count| {
+                        drawn += count;
+                        return log_binomial(drawn, *count);
+                    })
+                    .sum::<f64>();
+            })
+            .sum::<f64>(),
+        DrawingModel::WithoutReplacement => 0.0,
+    };
+
+    let log_likelihood = color_part + constant_part + size_log_likelihood(drawings, size, model);
+
+    if log_likelihood.is_finite() {
+        return Some(log_likelihood);
+    }
+
+    return None;
+}
+
+// Finds the most likely bag of the given size together with per color confidence intervals.
+// Returns None if no bag of that size could have produced the drawings.
+pub fn estimate_bag(drawings: &[Drawing], size: u32, model: DrawingModel) -> Option<BagEstimate> {
+    let observations = observe(drawings);
+    let all_colors = observations
+        .iter()
+        .map(|(color, observation)| (color.clone(), observation))
+        .collect();
+
+    let bag = distribute(&all_colors, size, model)?;
+    let log_likelihood = log_likelihood(&bag.clone().into_iter().collect(), drawings, model)?;
+
+    let intervals = observations
+        .iter()
+        .map(|(color, observation)| {
+            let others: BTreeMap<String, &Observation> = all_colors
+                .iter()
+                .filter(|(other, _)| *other != color)
+                .map(|(other, observation)| (other.clone(), *observation))
+                .collect();
+
+            // Profile likelihood: the best bag where this color has exactly the given count
+            let plausible = |count: u32| -> bool {
+                let Some(mut bag) = distribute(&others, size - count, model) else {
+                    return false;
+                };
+                bag.insert(color.clone(), count);
+
+                return self::log_likelihood(&bag.into_iter().collect(), drawings, model)
+                    .is_some_and(|profile| log_likelihood - profile <= CONFIDENCE_THRESHOLD);
+            };
+
+            let estimate = bag[color];
+            let lower = (minimum_count(observation, model)..=estimate)
+                .find(|count| plausible(*count))
+                .unwrap_or(estimate);
+            let upper = (estimate..=size)
+                .rev()
+                .find(|count| plausible(*count))
+                .unwrap_or(estimate);
+
+            return (color.clone(), (lower, upper));
+        })
+        .collect();
+
+    return Some(BagEstimate {
+        bag,
+        log_likelihood,
+        intervals,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use super::{estimate_bag, log_likelihood, DrawingModel};
+    use crate::task_02::{parse_game, Drawing};
+
+    fn example_drawings() -> Vec<Drawing> {
+        let (_, drawings) =
+            parse_game("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green").unwrap();
+
+        return drawings;
+    }
+
+    #[test]
+    fn estimate_bag_follows_observed_proportions_with_replacement() {
+        let estimate =
+            estimate_bag(&example_drawings(), 36, DrawingModel::WithReplacement).unwrap();
+
+        let expected = BTreeMap::from([
+            (String::from("blue"), 18),
+            (String::from("green"), 8),
+            (String::from("red"), 10),
+        ]);
+
+        assert_eq!(estimate.bag, expected);
+
+        // 9 of 18 observed cubes were blue, so about a quarter to three quarters of the bag is plausible
+        let expected_intervals = BTreeMap::from([
+            (String::from("blue"), (11, 25)),
+            (String::from("green"), (3, 15)),
+            (String::from("red"), (4, 18)),
+        ]);
+
+        assert_eq!(estimate.intervals, expected_intervals);
+    }
+
+    #[test]
+    fn estimate_bag_needs_enough_cubes_without_replacement() {
+        let drawings = example_drawings();
+
+        assert_eq!(
+            estimate_bag(&drawings, 11, DrawingModel::WithoutReplacement),
+            None
+        );
+
+        let estimate = estimate_bag(&drawings, 12, DrawingModel::WithoutReplacement).unwrap();
+        let expected = BTreeMap::from([
+            (String::from("blue"), 6),
+            (String::from("green"), 2),
+            (String::from("red"), 4),
+        ]);
+
+        assert_eq!(estimate.bag, expected);
+    }
+
+    #[test]
+    fn log_likelihood_rejects_impossible_bags() {
+        let drawings = example_drawings();
+        let bag: Drawing = HashMap::from([
+            (String::from("red"), 12),
+            (String::from("green"), 13),
+            (String::from("blue"), 5),
+        ]);
+
+        assert_eq!(
+            log_likelihood(&bag, &drawings, DrawingModel::WithoutReplacement),
+            None
+        );
+        assert!(log_likelihood(&bag, &drawings, DrawingModel::WithReplacement).is_some());
+    }
+}
@@ -1,4 +1,6 @@
-use std::{error::Error, fs};
+use std::{collections::VecDeque, error::Error, fmt, fs, path::Path, time::Instant};
+
+use crate::random::Lcg;
 
 /*
  * Despite my intention to use structs in a Haskell-like manner
@@ -32,173 +34,319 @@ struct Schematic {
     symbols: Vec<Symbol>,
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Cell {
+    Empty,
+    Part(usize),
+    Symbol(usize),
+}
+
 /*
- * span_digit_parts is a helper function for parse_schematic.
- *
- * The intention is to aid in grouping digit_parts for the construction of the part_numbers of a Schematic.
- * digit_parts are given as a alice of tuples of char_index and char for a line.
- * span_digit_parts is not concerned with filtering out chars by character class,
- * but instead only looks at the char_index and cuts when there's a hole between indices.
- *
- * The return value is a tuple of the first consecutive run of digits, followed by remaining digits (to be cut later).
- *
- * I'd have prefered something like a group_by here, but did not manage to get that working.
+ * A dense grid with one Cell per char of the schematic.
+ * Cells refer to the part_numbers and symbols of the Schematic by index,
+ * so that looking up what is at a Coordinate takes constant time.
  */
-fn span_digit_parts(digit_parts: &[(usize, char)]) -> (&[(usize, char)], &[(usize, char)]) {
-    if digit_parts.len() < 2 {
-        return (digit_parts, &[]);
-    }
+#[derive(PartialEq, Debug)]
+struct SchematicIndex {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
 
-    let mut cut: usize = 0;
-    for (index, current_digit_part) in digit_parts.iter().enumerate() {
-        if index == 0 {
-            continue;
+impl SchematicIndex {
+    fn get(&self, line_index: i32, char_index: i32) -> Cell {
+        if line_index < 0 || char_index < 0 {
+            return Cell::Empty;
         }
 
-        let previous_digit_part = digit_parts[index - 1];
-        if current_digit_part.0 != previous_digit_part.0 + 1 {
-            cut = index;
-            break;
+        let (line_index, char_index) = (line_index as usize, char_index as usize);
+        if line_index >= self.height || char_index >= self.width {
+            return Cell::Empty;
         }
-    }
 
-    if cut <= 0 {
-        return (digit_parts, &[]);
+        self.cells[line_index * self.width + char_index]
     }
+}
 
-    return (&digit_parts[..cut], &digit_parts[cut..]);
+#[derive(Debug, PartialEq)]
+struct OverflowError {
+    // Where the part number starts
+    coordinate: Coordinate,
 }
 
-fn parse_schematic(input: String) -> Schematic {
-    let (part_numbers_per_line, symbols_per_line): (Vec<_>, Vec<_>) = input
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Part number at {}:{} does not fit into 32 bits.",
+            self.coordinate.line_index, self.coordinate.char_index
+        )
+    }
+}
+
+impl Error for OverflowError {}
+
+/*
+ * parse_schematic walks the input once, char by char,
+ * extending the current part number for as long as digits follow each other.
+ * Lines of different length are padded with Cell::Empty in the SchematicIndex.
+ */
+fn parse_schematic(input: String) -> Result<(Schematic, SchematicIndex), OverflowError> {
+    let width = input
         .lines()
-        .enumerate()
-        .map(|(line_index, line)| -> (Vec<PartNumber>, Vec<Symbol>) {
-            let (digit_parts, symbols): (Vec<_>, Vec<_>) = line
-                .chars()
-                .enumerate()
-                .filter(|(_, c)| *c != '.')
-                .partition(|line_part| line_part.1.is_digit(10));
-
-            let symbols: Vec<_> = symbols
-                .iter()
-                .map(|(char_index, label)| -> Symbol {
-                    Symbol {
-                        label: label.to_string(),
-                        coordinates: Vec::from([Coordinate {
-                            line_index: line_index.try_into().unwrap(),
-                            char_index: (*char_index).try_into().unwrap(),
-                        }]),
-                    }
-                })
-                .collect();
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let height = input.lines().count();
 
-            let mut digit_parts = digit_parts.as_slice();
-            let mut part_numbers: Vec<PartNumber> = Vec::new();
+    let mut schematic = Schematic {
+        part_numbers: Vec::new(),
+        symbols: Vec::new(),
+    };
+    let mut index = SchematicIndex {
+        width,
+        height,
+        cells: vec![Cell::Empty; width * height],
+    };
 
-            while digit_parts.len() > 0 {
-                let span = span_digit_parts(digit_parts);
-                let current_parts = span.0;
-                digit_parts = span.1;
+    for (line_index, line) in input.lines().enumerate() {
+        let mut current_part: Option<usize> = None;
 
-                part_numbers.push(PartNumber {
-                    number: current_parts
-                        .iter()
-                        .map(|(_, digit)| digit)
-                        .collect::<String>()
-                        .parse()
-                        .unwrap(),
-                    coordinates: current_parts
-                        .iter()
-                        .map(|(char_index, _)| -> Coordinate {
-                            Coordinate {
-                                line_index: line_index.try_into().unwrap(),
-                                char_index: (*char_index).try_into().unwrap(),
-                            }
-                        })
-                        .collect(),
-                })
+        for (char_index, c) in line.chars().enumerate() {
+            let coordinate = Coordinate {
+                line_index: line_index.try_into().unwrap(),
+                char_index: char_index.try_into().unwrap(),
+            };
+            let cell = &mut index.cells[line_index * width + char_index];
+
+            if let Some(digit) = c.to_digit(10) {
+                let part_index = *current_part.get_or_insert_with(|| {
+                    schematic.part_numbers.push(PartNumber {
+                        number: 0,
+                        coordinates: Vec::new(),
+                    });
+
+                    schematic.part_numbers.len() - 1
+                });
+
+                let part_number = &mut schematic.part_numbers[part_index];
+                part_number.coordinates.push(coordinate);
+                part_number.number = part_number
+                    .number
+                    .checked_mul(10)
+                    .and_then(|number| number.checked_add(digit))
+                    .ok_or_else(|| OverflowError {
+                        coordinate: part_number.coordinates[0].clone(),
+                    })?;
+                *cell = Cell::Part(part_index);
+
+                continue;
+            }
+
+            current_part = None;
+
+            if c != '.' {
+                schematic.symbols.push(Symbol {
+                    label: c.to_string(),
+                    coordinates: Vec::from([coordinate]),
+                });
+                *cell = Cell::Symbol(schematic.symbols.len() - 1);
             }
+        }
+    }
+
+    Ok((schematic, index))
+}
 
-            return (part_numbers, symbols);
+/*
+ * The adjacency between part_numbers and symbols of a Schematic as a bipartite graph.
+ * Both directions are stored as indices into the part_numbers and symbols of the Schematic.
+ */
+#[derive(PartialEq, Debug)]
+struct AdjacencyGraph {
+    symbols_by_part: Vec<Vec<usize>>,
+    parts_by_symbol: Vec<Vec<usize>>,
+}
+
+// The coordinates surrounding a part number line by line, including those outside of the schematic
+fn neighbours(part_number: &PartNumber) -> impl Iterator<Item = Coordinate> + '_ {
+    let bounds = part_number
+        .coordinates
+        .first()
+        .zip(part_number.coordinates.last());
+
+    bounds.into_iter().flat_map(|(first, last)| {
+        (first.line_index - 1..=first.line_index + 1).flat_map(move |line_index| {
+            (first.char_index - 1..=last.char_index + 1)
+                .filter(move |char_index| {
+                    line_index != first.line_index
+                        || *char_index < first.char_index
+                        || *char_index > last.char_index
+                })
+                .map(move |char_index| Coordinate {
+                    line_index,
+                    char_index,
+                })
         })
-        .unzip();
+    })
+}
 
-    Schematic {
-        part_numbers: part_numbers_per_line.concat(),
-        symbols: symbols_per_line.concat(),
+// Builds the AdjacencyGraph by looking at the cells surrounding each part number in the SchematicIndex
+fn adjacency_graph(schematic: &Schematic, index: &SchematicIndex) -> AdjacencyGraph {
+    let mut graph = AdjacencyGraph {
+        symbols_by_part: vec![Vec::new(); schematic.part_numbers.len()],
+        parts_by_symbol: vec![Vec::new(); schematic.symbols.len()],
+    };
+
+    for (part_index, part_number) in schematic.part_numbers.iter().enumerate() {
+        for neighbour in neighbours(part_number) {
+            if let Cell::Symbol(symbol_index) =
+                index.get(neighbour.line_index, neighbour.char_index)
+            {
+                graph.symbols_by_part[part_index].push(symbol_index);
+                graph.parts_by_symbol[symbol_index].push(part_index);
+            }
+        }
     }
+
+    graph
 }
 
-fn neighbours(coordinates: &Vec<Coordinate>) -> Vec<Coordinate> {
-    coordinates
+// A connected component of the AdjacencyGraph given as indices of part_numbers and symbols
+#[derive(PartialEq, Debug)]
+struct Component {
+    parts: Vec<usize>,
+    symbols: Vec<usize>,
+}
+
+fn components(graph: &AdjacencyGraph) -> Vec<Component> {
+    let mut visited_parts = vec![false; graph.symbols_by_part.len()];
+    let mut visited_symbols = vec![false; graph.parts_by_symbol.len()];
+    let mut components = Vec::new();
+
+    for start in 0..graph.symbols_by_part.len() {
+        if visited_parts[start] {
+            continue;
+        }
+
+        visited_parts[start] = true;
+        let mut component = Component {
+            parts: Vec::new(),
+            symbols: Vec::new(),
+        };
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(part_index) = queue.pop_front() {
+            component.parts.push(part_index);
+
+            for symbol_index in graph.symbols_by_part[part_index].iter() {
+                if visited_symbols[*symbol_index] {
+                    continue;
+                }
+
+                visited_symbols[*symbol_index] = true;
+                component.symbols.push(*symbol_index);
+
+                for next_part in graph.parts_by_symbol[*symbol_index].iter() {
+                    if !visited_parts[*next_part] {
+                        visited_parts[*next_part] = true;
+                        queue.push_back(*next_part);
+                    }
+                }
+            }
+        }
+
+        component.parts.sort();
+        component.symbols.sort();
+        components.push(component);
+    }
+
+    // Symbols without any adjacent part_numbers form components of their own
+    for (symbol_index, visited) in visited_symbols.iter().enumerate() {
+        if !visited {
+            components.push(Component {
+                parts: Vec::new(),
+                symbols: Vec::from([symbol_index]),
+            });
+        }
+    }
+
+    components
+}
+
+// Indices of symbols with exactly part_count adjacent part_numbers, optionally restricted to a label
+fn symbols_with_part_count(
+    schematic: &Schematic,
+    graph: &AdjacencyGraph,
+    label: Option<&str>,
+    part_count: usize,
+) -> Vec<usize> {
+    graph
+        .parts_by_symbol
         .iter()
-        .flat_map(|center| {
-            Vec::from([
-                Coordinate {
-                    line_index: center.line_index - 1,
-                    char_index: center.char_index - 1,
-                },
-                Coordinate {
-                    line_index: center.line_index - 1,
-                    char_index: center.char_index,
-                },
-                Coordinate {
-                    line_index: center.line_index - 1,
-                    char_index: center.char_index + 1,
-                },
-                Coordinate {
-                    line_index: center.line_index,
-                    char_index: center.char_index - 1,
-                },
-                Coordinate {
-                    line_index: center.line_index,
-                    char_index: center.char_index + 1,
-                },
-                Coordinate {
-                    line_index: center.line_index + 1,
-                    char_index: center.char_index - 1,
-                },
-                Coordinate {
-                    line_index: center.line_index + 1,
-                    char_index: center.char_index,
-                },
-                Coordinate {
-                    line_index: center.line_index + 1,
-                    char_index: center.char_index + 1,
-                },
-            ])
+        .enumerate()
+        .filter(|(symbol_index, parts)| {
+            parts.len() == part_count
+                && label.is_none_or(|label| schematic.symbols[*symbol_index].label == label)
         })
-        .filter(|candidate| coordinates.iter().all(|existing| candidate != existing))
+        .map(|(symbol_index, _)| symbol_index)
         .collect()
 }
 
+// Indices of part_numbers that are adjacent to more than one symbol
+fn parts_with_multiple_symbols(graph: &AdjacencyGraph) -> Vec<usize> {
+    graph
+        .symbols_by_part
+        .iter()
+        .enumerate()
+        .filter(|(_, symbols)| symbols.len() > 1)
+        .map(|(part_index, _)| part_index)
+        .collect()
+}
+
+fn to_dot(schematic: &Schematic, graph: &AdjacencyGraph) -> String {
+    let mut lines = Vec::from(["graph schematic {".to_string()]);
+
+    for (part_index, part_number) in schematic.part_numbers.iter().enumerate() {
+        lines.push(format!(
+            "  p{} [label=\"{}\", shape=box];",
+            part_index, part_number.number
+        ));
+    }
+
+    for (symbol_index, symbol) in schematic.symbols.iter().enumerate() {
+        lines.push(format!(
+            "  s{} [label=\"{}\", shape=circle];",
+            symbol_index,
+            symbol.label.replace('\\', "\\\\").replace('"', "\\\"")
+        ));
+    }
+
+    for (part_index, symbols) in graph.symbols_by_part.iter().enumerate() {
+        for symbol_index in symbols {
+            lines.push(format!("  p{} -- s{};", part_index, symbol_index));
+        }
+    }
+
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
 fn first() -> Result<(), Box<dyn Error>> {
     let paths = ["./inputs/03/example-1.txt", "./inputs/03/input.txt"];
 
     for path in paths {
         println!("File {}:", path);
         let contents = fs::read_to_string(path)?;
-        let schematic = parse_schematic(contents);
+        let (schematic, index) = parse_schematic(contents)?;
+        let graph = adjacency_graph(&schematic, &index);
 
-        let symbol_coordinates: Vec<_> = schematic
-            .symbols
+        let sum: u32 = schematic
+            .part_numbers
             .iter()
-            .flat_map(|symbol| symbol.coordinates.iter())
-            .collect();
-
-        let part_numbers_with_symbol = schematic.part_numbers.iter().filter(|part_number| {
-            let neighbour_coordinates = neighbours(&part_number.coordinates);
-
-            return neighbour_coordinates.iter().any(|neighbour_coordinate| {
-                symbol_coordinates
-                    .iter()
-                    .any(|symbol_coordinate| neighbour_coordinate == *symbol_coordinate)
-            });
-        });
-
-        let sum: u32 = part_numbers_with_symbol
-            .map(|part_number| part_number.number)
+            .zip(graph.symbols_by_part.iter())
+            .filter(|(_, symbols)| !symbols.is_empty())
+            .map(|(part_number, _)| part_number.number)
             .sum();
 
         println!("Sum of part numbers with symbols: {}", sum);
@@ -213,47 +361,346 @@ fn second() -> Result<(), Box<dyn Error>> {
     for path in paths {
         println!("File {}:", path);
         let contents = fs::read_to_string(path)?;
-        let schematic = parse_schematic(contents);
+        let (schematic, index) = parse_schematic(contents)?;
+        let graph = adjacency_graph(&schematic, &index);
 
-        let gears = schematic
-            .symbols
-            .iter()
-            .filter(|symbol| symbol.label == "*");
-
-        let gear_part_numbers = gears
-            .map(|gear| {
-                let gear_neighbours = neighbours(&gear.coordinates);
-
-                let adjacent_part_numbers: Vec<_> = schematic
-                    .part_numbers
-                    .iter()
-                    .filter(|part_number| {
-                        part_number.coordinates.iter().any(|part_coordinate| {
-                            gear_neighbours.iter().any(|gear_neighbour_coordinate| {
-                                part_coordinate == gear_neighbour_coordinate
-                            })
-                        })
-                    })
-                    .collect();
-
-                return adjacent_part_numbers;
+        let gear_ratios = symbols_with_part_count(&schematic, &graph, Some("*"), 2)
+            .into_iter()
+            .map(|gear_index| gear_ratio(&schematic, &graph, gear_index));
+
+        println!("Sum of gear ratios: {}", gear_ratios.sum::<u32>());
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Highlight {
+    Empty,
+    CountedPart,
+    UncountedPart,
+    Gear,
+    Symbol,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum RenderFormat {
+    Ansi,
+    Html,
+}
+
+// Lays the original input out as a grid of chars, each tagged with how it was treated by first() and second().
+// Lines shorter than the schematic's width are padded with '.'.
+fn highlighted_grid(
+    input: &str,
+    schematic: &Schematic,
+    index: &SchematicIndex,
+    graph: &AdjacencyGraph,
+) -> Vec<Vec<(char, Highlight)>> {
+    let gears = symbols_with_part_count(schematic, graph, Some("*"), 2);
+
+    input
+        .lines()
+        .enumerate()
+        .map(|(line_index, line)| {
+            let mut chars = line.chars();
+
+            (0..index.width)
+                .map(|char_index| {
+                    let c = chars.next().unwrap_or('.');
+                    let highlight = match index.cells[line_index * index.width + char_index] {
+                        Cell::Empty => Highlight::Empty,
+                        Cell::Part(part_index) if graph.symbols_by_part[part_index].is_empty() => {
+                            Highlight::UncountedPart
+                        }
+                        Cell::Part(_) => Highlight::CountedPart,
+                        Cell::Symbol(symbol_index) if gears.contains(&symbol_index) => {
+                            Highlight::Gear
+                        }
+                        Cell::Symbol(_) => Highlight::Symbol,
+                    };
+
+                    (c, highlight)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn gear_ratio(schematic: &Schematic, graph: &AdjacencyGraph, gear_index: usize) -> u32 {
+    graph.parts_by_symbol[gear_index]
+        .iter()
+        .map(|part_index| schematic.part_numbers[*part_index].number)
+        .product()
+}
+
+fn render(
+    input: &str,
+    schematic: &Schematic,
+    index: &SchematicIndex,
+    graph: &AdjacencyGraph,
+    format: RenderFormat,
+) -> String {
+    let grid = highlighted_grid(input, schematic, index, graph);
+    let gears = symbols_with_part_count(schematic, graph, Some("*"), 2);
+
+    let lines = grid.iter().map(|line| {
+        line.iter()
+            .map(|(c, highlight)| match format {
+                RenderFormat::Ansi => match highlight {
+                    Highlight::Empty => format!("\x1b[2m{}\x1b[0m", c),
+                    Highlight::CountedPart => format!("\x1b[32m{}\x1b[0m", c),
+                    Highlight::UncountedPart => format!("\x1b[31m{}\x1b[0m", c),
+                    Highlight::Gear => format!("\x1b[1;33m{}\x1b[0m", c),
+                    Highlight::Symbol => format!("\x1b[36m{}\x1b[0m", c),
+                },
+                RenderFormat::Html => {
+                    let c = match c {
+                        '<' => "&lt;".to_string(),
+                        '>' => "&gt;".to_string(),
+                        '&' => "&amp;".to_string(),
+                        c => c.to_string(),
+                    };
+
+                    match highlight {
+                        Highlight::Empty => c,
+                        Highlight::CountedPart => format!("<span class=\"counted\">{}</span>", c),
+                        Highlight::UncountedPart => {
+                            format!("<span class=\"uncounted\">{}</span>", c)
+                        }
+                        Highlight::Gear => format!("<span class=\"gear\">{}</span>", c),
+                        Highlight::Symbol => format!("<span class=\"symbol\">{}</span>", c),
+                    }
+                }
             })
-            .filter(|adjacent_part_numbers| adjacent_part_numbers.len() == 2);
+            .collect::<String>()
+    });
 
-        let gear_ratios = gear_part_numbers.map(|part_numbers| {
-            part_numbers
-                .iter()
-                .map(|part_number| part_number.number)
-                .product::<u32>()
-        });
+    let ratios = gears.iter().map(|gear_index| {
+        let coordinate = &schematic.symbols[*gear_index].coordinates[0];
+        let parts = graph.parts_by_symbol[*gear_index]
+            .iter()
+            .map(|part_index| schematic.part_numbers[*part_index].number.to_string())
+            .collect::<Vec<_>>();
 
-        println!("Sum of gear ratios: {}", gear_ratios.sum::<u32>());
+        format!(
+            "Gear at {}:{}: {} = {}",
+            coordinate.line_index,
+            coordinate.char_index,
+            parts.join(" * "),
+            gear_ratio(schematic, graph, *gear_index)
+        )
+    });
+
+    let body = lines
+        .chain([String::new()])
+        .chain(ratios)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    match format {
+        RenderFormat::Ansi => body,
+        RenderFormat::Html => [
+            "<!DOCTYPE html>",
+            "<html>",
+            "<head>",
+            "<style>",
+            "pre { color: #999; }",
+            ".counted { color: #080; font-weight: bold; }",
+            ".uncounted { color: #c00; }",
+            ".gear { color: #000; background: #fd0; }",
+            ".symbol { color: #06c; }",
+            "</style>",
+            "</head>",
+            "<body>",
+            &format!("<pre>{}</pre>", body),
+            "</body>",
+            "</html>",
+        ]
+        .join("\n"),
+    }
+}
+
+fn describe_part(schematic: &Schematic, part_index: usize) -> String {
+    let part_number = &schematic.part_numbers[part_index];
+    let start = &part_number.coordinates[0];
+
+    format!(
+        "{} at {}:{}",
+        part_number.number, start.line_index, start.char_index
+    )
+}
+
+fn describe_symbol(schematic: &Schematic, symbol_index: usize) -> String {
+    let symbol = &schematic.symbols[symbol_index];
+    let start = &symbol.coordinates[0];
+
+    format!(
+        "{} at {}:{}",
+        symbol.label, start.line_index, start.char_index
+    )
+}
+
+// Puzzle part numbers have at most three digits, generated ones as well so that they never overflow
+const GENERATED_DIGITS: usize = 3;
+const BENCHMARK_SIZES: [usize; 4] = [250, 500, 1000, 2000];
+
+// Generates a pseudo random schematic, so that benchmarks are reproducible
+fn generate_schematic(width: usize, height: usize, seed: u64) -> String {
+    let symbols = ['*', '#', '+', '$', '/', '@', '=', '%', '-', '&'];
+    let mut random = Lcg::new(seed);
+
+    (0..height)
+        .map(|_| {
+            let mut digits = 0;
+
+            (0..width)
+                .map(|_| {
+                    let roll = random.below(100);
+
+                    let c = match roll {
+                        60..=89 if digits < GENERATED_DIGITS => {
+                            char::from_digit((roll % 10) as u32, 10).unwrap()
+                        }
+                        0..=89 => '.',
+                        _ => symbols[(roll % 10) as usize],
+                    };
+                    digits = if c.is_ascii_digit() { digits + 1 } else { 0 };
+
+                    c
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Times parsing and adjacency on growing schematics; the time per cell should stay roughly constant
+fn benchmark() -> Result<(), Box<dyn Error>> {
+    for size in BENCHMARK_SIZES {
+        let contents = generate_schematic(size, size, 42);
+        let cells = size * size;
+
+        let start = Instant::now();
+        let (schematic, index) = parse_schematic(contents)?;
+        let graph = adjacency_graph(&schematic, &index);
+        let elapsed = start.elapsed();
+
+        let counted = graph
+            .symbols_by_part
+            .iter()
+            .filter(|symbols| !symbols.is_empty())
+            .count();
+
+        println!(
+            "{} cells: {} part numbers ({} counted), {:?}, {:.1}ns per cell",
+            cells,
+            schematic.part_numbers.len(),
+            counted,
+            elapsed,
+            elapsed.as_nanos() as f64 / cells as f64
+        );
+    }
+
+    Ok(())
+}
+
+fn analyse(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let paths = ["./inputs/03/example-1.txt", "./inputs/03/input.txt"];
+    let usage = "Usage: 3 components | symbols <label|any> <part count> | shared | dot \
+        | render ansi | render html <output directory> | benchmark";
+
+    if args == ["benchmark"] {
+        return benchmark();
+    }
+
+    for path in paths {
+        println!("File {}:", path);
+        let contents = fs::read_to_string(path)?;
+        let (schematic, index) = parse_schematic(contents.clone())?;
+        let graph = adjacency_graph(&schematic, &index);
+
+        match args.split_first() {
+            Some((command, [])) if command == "components" => {
+                for component in components(&graph) {
+                    let parts = component
+                        .parts
+                        .iter()
+                        .map(|part_index| describe_part(&schematic, *part_index))
+                        .collect::<Vec<_>>();
+                    let symbols = component
+                        .symbols
+                        .iter()
+                        .map(|symbol_index| describe_symbol(&schematic, *symbol_index))
+                        .collect::<Vec<_>>();
+
+                    println!("[{}] / [{}]", parts.join(", "), symbols.join(", "));
+                }
+            }
+            Some((command, [label, part_count])) if command == "symbols" => {
+                let label = Some(label.as_str()).filter(|label| *label != "any");
+                let part_count = part_count.parse::<usize>()?;
+
+                for symbol_index in symbols_with_part_count(&schematic, &graph, label, part_count) {
+                    let parts = graph.parts_by_symbol[symbol_index]
+                        .iter()
+                        .map(|part_index| describe_part(&schematic, *part_index))
+                        .collect::<Vec<_>>();
+
+                    println!(
+                        "{}: {}",
+                        describe_symbol(&schematic, symbol_index),
+                        parts.join(", ")
+                    );
+                }
+            }
+            Some((command, [])) if command == "shared" => {
+                for part_index in parts_with_multiple_symbols(&graph) {
+                    let symbols = graph.symbols_by_part[part_index]
+                        .iter()
+                        .map(|symbol_index| describe_symbol(&schematic, *symbol_index))
+                        .collect::<Vec<_>>();
+
+                    println!(
+                        "{}: {}",
+                        describe_part(&schematic, part_index),
+                        symbols.join(", ")
+                    );
+                }
+            }
+            Some((command, [format])) if command == "render" && format == "ansi" => {
+                println!(
+                    "{}",
+                    render(&contents, &schematic, &index, &graph, RenderFormat::Ansi)
+                );
+            }
+            Some((command, [format, directory])) if command == "render" && format == "html" => {
+                let file_name = Path::new(path).with_extension("html");
+                let file_name = file_name.file_name().ok_or(usage)?;
+                let output = Path::new(directory).join(file_name);
+
+                fs::write(
+                    &output,
+                    render(&contents, &schematic, &index, &graph, RenderFormat::Html),
+                )?;
+                println!("Wrote {}", output.display());
+            }
+            Some((command, [])) if command == "dot" => {
+                println!("{}", to_dot(&schematic, &graph));
+            }
+            _ => return Err(usage.into()),
+        }
     }
 
     Ok(())
 }
 
 pub fn main() -> Result<(), Box<dyn Error>> {
+    let args = crate::task_args();
+    if !args.is_empty() {
+        return analyse(&args);
+    }
+
     println!("03-1:");
     first()?;
     println!("03-2:");
@@ -264,7 +711,11 @@ pub fn main() -> Result<(), Box<dyn Error>> {
 mod tests {
     use std::{error::Error, fs};
 
-    use crate::task_03::{neighbours, parse_schematic, Coordinate, PartNumber, Schematic, Symbol};
+    use crate::task_03::{
+        adjacency_graph, components, generate_schematic, neighbours, parse_schematic,
+        parts_with_multiple_symbols, render, symbols_with_part_count, to_dot, Cell, Component,
+        Coordinate, OverflowError, PartNumber, RenderFormat, Schematic, Symbol, GENERATED_DIGITS,
+    };
 
     #[test]
     fn should_parse_example_as_expected() -> Result<(), Box<dyn Error>> {
@@ -499,7 +950,7 @@ mod tests {
             .to_vec(),
         };
 
-        let actual = parse_schematic(example_data);
+        let (actual, _) = parse_schematic(example_data).unwrap();
 
         assert_eq!(actual, expected);
 
@@ -507,74 +958,101 @@ mod tests {
     }
 
     #[test]
-    fn neighbours_should_be_empty_for_an_empty_list() {
-        assert_eq!(neighbours(&Vec::new()), Vec::new());
+    fn adjacency_graph_should_connect_example_gears() -> Result<(), Box<dyn Error>> {
+        let (schematic, index) = parse_schematic(fs::read_to_string("./inputs/03/example-1.txt")?)?;
+        let graph = adjacency_graph(&schematic, &index);
+
+        assert_eq!(graph.parts_by_symbol[0], [0, 2].to_vec());
+        assert_eq!(graph.symbols_by_part[1], Vec::new());
+        assert_eq!(
+            symbols_with_part_count(&schematic, &graph, Some("*"), 2),
+            [0, 5].to_vec()
+        );
+        assert_eq!(
+            symbols_with_part_count(&schematic, &graph, None, 1),
+            [1, 2, 3, 4].to_vec()
+        );
+
+        Ok(())
     }
 
     #[test]
-    fn neighbours_should_be_as_expected() {
+    fn components_should_join_parts_sharing_a_symbol() {
+        let (schematic, index) = parse_schematic("12.\n.*.\n3#4".to_string()).unwrap();
+        let graph = adjacency_graph(&schematic, &index);
+
+        let expected = Vec::from([Component {
+            parts: [0, 1, 2].to_vec(),
+            symbols: [0, 1].to_vec(),
+        }]);
+
+        assert_eq!(components(&graph), expected);
+        assert_eq!(parts_with_multiple_symbols(&graph), [1, 2].to_vec());
+        assert!(to_dot(&schematic, &graph).contains("p0 -- s0;"));
+    }
+
+    #[test]
+    fn render_should_mark_counted_parts_and_gears() -> Result<(), Box<dyn Error>> {
+        let contents = fs::read_to_string("./inputs/03/example-1.txt")?;
+        let (schematic, index) = parse_schematic(contents.clone())?;
+        let graph = adjacency_graph(&schematic, &index);
+
+        let ansi = render(&contents, &schematic, &index, &graph, RenderFormat::Ansi);
+        let html = render(&contents, &schematic, &index, &graph, RenderFormat::Html);
+
+        assert!(ansi.starts_with("\x1b[32m4\x1b[0m"));
+        assert!(ansi.contains("Gear at 1:3: 467 * 35 = 16345"));
+        assert!(ansi.contains("Gear at 8:5: 755 * 598 = 451490"));
+        assert!(html.contains("..<span class=\"uncounted\">1</span>"));
+        assert!(html.contains("<span class=\"gear\">*</span>"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn render_should_keep_original_chars_and_size() {
+        let contents = "007*..\n......\n..5...".to_string();
+        let (schematic, index) = parse_schematic(contents.clone()).unwrap();
+        let graph = adjacency_graph(&schematic, &index);
+
         let expected = [
-            Coordinate {
-                line_index: -1,
-                char_index: 0,
-            },
-            Coordinate {
-                line_index: -1,
-                char_index: 1,
-            },
-            Coordinate {
-                line_index: -1,
-                char_index: 2,
-            },
-            Coordinate {
-                line_index: 0,
-                char_index: 0,
-            },
-            Coordinate {
-                line_index: 1,
-                char_index: 0,
-            },
-            Coordinate {
-                line_index: 1,
-                char_index: 1,
-            },
-            Coordinate {
-                line_index: 1,
-                char_index: 2,
-            },
-            Coordinate {
-                line_index: -1,
-                char_index: 1,
-            },
-            Coordinate {
-                line_index: -1,
-                char_index: 2,
-            },
-            Coordinate {
-                line_index: -1,
-                char_index: 3,
-            },
-            Coordinate {
-                line_index: 0,
-                char_index: 3,
-            },
-            Coordinate {
-                line_index: 1,
-                char_index: 1,
-            },
-            Coordinate {
-                line_index: 1,
-                char_index: 2,
-            },
-            Coordinate {
-                line_index: 1,
-                char_index: 3,
-            },
-        ]
-        .to_vec();
+            "\x1b[32m0\x1b[0m\x1b[32m0\x1b[0m\x1b[32m7\x1b[0m\x1b[36m*\x1b[0m\x1b[2m.\x1b[0m\x1b[2m.\x1b[0m",
+            "\x1b[2m.\x1b[0m\x1b[2m.\x1b[0m\x1b[2m.\x1b[0m\x1b[2m.\x1b[0m\x1b[2m.\x1b[0m\x1b[2m.\x1b[0m",
+            "\x1b[2m.\x1b[0m\x1b[2m.\x1b[0m\x1b[31m5\x1b[0m\x1b[2m.\x1b[0m\x1b[2m.\x1b[0m\x1b[2m.\x1b[0m",
+        ];
+        let ansi = render(&contents, &schematic, &index, &graph, RenderFormat::Ansi);
+
+        assert_eq!(ansi.lines().take(3).collect::<Vec<_>>(), expected.to_vec());
+    }
+
+    #[test]
+    fn parse_schematic_should_index_every_cell() {
+        let (schematic, index) = parse_schematic("12.\n.*.\n3#4".to_string()).unwrap();
+
+        assert_eq!((index.width, index.height), (3, 3));
+        assert_eq!(index.get(0, 1), Cell::Part(0));
+        assert_eq!(index.get(1, 1), Cell::Symbol(0));
+        assert_eq!(index.get(1, 2), Cell::Empty);
+        assert_eq!(index.get(-1, 0), Cell::Empty);
+        assert_eq!(index.get(0, 3), Cell::Empty);
+        assert_eq!(schematic.part_numbers[0].number, 12);
+    }
 
-        let actual = neighbours(
-            &[
+    #[test]
+    fn neighbours_should_be_empty_for_an_empty_part_number() {
+        let part_number = PartNumber {
+            number: 0,
+            coordinates: Vec::new(),
+        };
+
+        assert_eq!(neighbours(&part_number).count(), 0);
+    }
+
+    #[test]
+    fn neighbours_should_be_as_expected() {
+        let part_number = PartNumber {
+            number: 12,
+            coordinates: [
                 Coordinate {
                     line_index: 0,
                     char_index: 1,
@@ -585,8 +1063,92 @@ mod tests {
                 },
             ]
             .to_vec(),
-        );
+        };
 
-        assert_eq!(actual, expected);
+        let expected = [
+            (-1, 0),
+            (-1, 1),
+            (-1, 2),
+            (-1, 3),
+            (0, 0),
+            (0, 3),
+            (1, 0),
+            (1, 1),
+            (1, 2),
+            (1, 3),
+        ]
+        .map(|(line_index, char_index)| Coordinate {
+            line_index,
+            char_index,
+        })
+        .to_vec();
+
+        assert_eq!(neighbours(&part_number).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn neighbours_should_look_up_cells_in_the_index() {
+        let (schematic, index) = parse_schematic("12.\n.*.\n3#4".to_string()).unwrap();
+
+        let cells = |part_index: usize| {
+            neighbours(&schematic.part_numbers[part_index])
+                .map(|neighbour| index.get(neighbour.line_index, neighbour.char_index))
+                .filter(|cell| *cell != Cell::Empty)
+                .collect::<Vec<_>>()
+        };
+
+        // Cells outside of the schematic are empty
+        assert_eq!(cells(0), [Cell::Symbol(0)].to_vec());
+        assert_eq!(cells(1), [Cell::Symbol(0), Cell::Symbol(1)].to_vec());
+        assert_eq!(cells(2), [Cell::Symbol(0), Cell::Symbol(1)].to_vec());
+    }
+
+    #[test]
+    fn parse_schematic_should_handle_generated_schematics() {
+        let (schematic, index) = parse_schematic(generate_schematic(300, 200, 7)).unwrap();
+        let graph = adjacency_graph(&schematic, &index);
+
+        assert_eq!(index.cells.len(), 300 * 200);
+        for (part_index, part_number) in schematic.part_numbers.iter().enumerate() {
+            for coordinate in part_number.coordinates.iter() {
+                assert_eq!(
+                    index.get(coordinate.line_index, coordinate.char_index),
+                    Cell::Part(part_index)
+                );
+            }
+
+            for symbol_index in graph.symbols_by_part[part_index].iter() {
+                assert!(graph.parts_by_symbol[*symbol_index].contains(&part_index));
+            }
+        }
+    }
+
+    #[test]
+    fn parse_schematic_should_handle_generated_sizes() {
+        // The benchmark command covers the large sizes, these keep the debug test run quick
+        for size in [10, 25, 100] {
+            let (schematic, index) = parse_schematic(generate_schematic(size, size, 42)).unwrap();
+
+            assert_eq!(index.cells.len(), size * size);
+            assert!(!schematic.part_numbers.is_empty());
+            assert!(schematic
+                .part_numbers
+                .iter()
+                .all(|part_number| part_number.coordinates.len() <= GENERATED_DIGITS));
+        }
+    }
+
+    #[test]
+    fn parse_schematic_should_report_overflowing_part_numbers() {
+        let result = parse_schematic("..*\n.4294967295\n..4294967296".to_string());
+
+        let expected = OverflowError {
+            coordinate: Coordinate {
+                line_index: 2,
+                char_index: 2,
+            },
+        };
+
+        assert_eq!(result.err(), Some(expected));
     }
 }
@@ -1,5 +1,9 @@
 use regex::Regex;
-use std::{error::Error, fs};
+use std::{collections::HashSet, error::Error, fmt, fs};
+
+mod big_uint;
+
+use big_uint::BigUint;
 
 #[derive(Debug, PartialEq)]
 struct Card {
@@ -39,14 +43,43 @@ fn count_matching_values(card: &Card) -> u32 {
         .unwrap_or(0)
 }
 
-fn card_value(card: &Card) -> u32 {
+#[derive(Debug, PartialEq)]
+struct OverflowError {
+    card_id: u32,
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Overflow while scoring card {}, try the big mode instead.",
+            self.card_id
+        )
+    }
+}
+
+impl Error for OverflowError {}
+
+fn card_value(card: &Card) -> Result<u32, OverflowError> {
+    let winning_count = count_matching_values(card);
+
+    if winning_count == 0 {
+        return Ok(0);
+    }
+
+    2_u32
+        .checked_pow(winning_count - 1)
+        .ok_or(OverflowError { card_id: card.id })
+}
+
+fn big_card_value(card: &Card) -> BigUint {
     let winning_count = count_matching_values(card);
 
     if winning_count == 0 {
-        return 0;
+        return BigUint::default();
     }
 
-    2_u32.pow(winning_count - 1)
+    BigUint::power_of_two(winning_count - 1)
 }
 
 pub fn first() -> Result<(), Box<dyn Error>> {
@@ -58,7 +91,12 @@ pub fn first() -> Result<(), Box<dyn Error>> {
 
         let cards = contents.lines().flat_map(parse_card);
 
-        let sum = cards.map(|card| card_value(&card)).sum::<u32>();
+        let mut sum: u32 = 0;
+        for card in cards {
+            sum = sum
+                .checked_add(card_value(&card)?)
+                .ok_or(OverflowError { card_id: card.id })?;
+        }
 
         println!("Sum of card values: {}", sum);
     }
@@ -67,27 +105,50 @@ pub fn first() -> Result<(), Box<dyn Error>> {
 }
 
 // Count cards and instances as per the second puzzle of the task
-fn count_cards(cards: Vec<Card>) -> u32 {
+fn count_cards(cards: Vec<Card>) -> Result<u32, OverflowError> {
     let mut total_cards: u32 = 0;
 
     let mut cards_and_counts = cards.iter().map(|card| (card, 1_u32)).collect::<Vec<_>>();
     let mut cards_and_counts: &mut [(&Card, u32)] = &mut cards_and_counts[..];
 
-    while cards_and_counts.len() > 0 {
+    while !cards_and_counts.is_empty() {
         let (first_card, first_card_count) = cards_and_counts[0];
         cards_and_counts = &mut cards_and_counts[1..];
 
-        total_cards += first_card_count;
+        let overflow = OverflowError {
+            card_id: first_card.id,
+        };
+        total_cards = total_cards.checked_add(first_card_count).ok_or(overflow)?;
 
         let matching_values: usize = count_matching_values(first_card)
             .try_into()
             .unwrap_or_default();
-        for card_index in 0..matching_values {
-            cards_and_counts[card_index].1 += first_card_count;
+        for (_, count) in cards_and_counts.iter_mut().take(matching_values) {
+            *count = count.checked_add(first_card_count).ok_or(OverflowError {
+                card_id: first_card.id,
+            })?;
         }
     }
 
-    return total_cards;
+    Ok(total_cards)
+}
+
+// Like count_cards, but with arbitrary precision for cascades that exceed u32
+fn big_count_cards(cards: &[Card]) -> BigUint {
+    let mut total_cards = BigUint::default();
+    let mut counts = vec![BigUint::from(1); cards.len()];
+
+    for (card_index, card) in cards.iter().enumerate() {
+        let count = counts[card_index].clone();
+        total_cards += &count;
+
+        let matching_values = count_matching_values(card) as usize;
+        for won in counts.iter_mut().skip(card_index + 1).take(matching_values) {
+            *won += &count;
+        }
+    }
+
+    total_cards
 }
 
 pub fn second() -> Result<(), Box<dyn Error>> {
@@ -99,13 +160,279 @@ pub fn second() -> Result<(), Box<dyn Error>> {
 
         let cards = contents.lines().flat_map(parse_card).collect::<Vec<_>>();
 
-        println!("Number of cards won: {}", count_cards(cards));
+        println!("Number of cards won: {}", count_cards(cards)?);
+    }
+
+    Ok(())
+}
+
+// How cards win copies of following cards during a cascade
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum WinRule {
+    // Each instance wins one copy of each of the next cards, one card per match
+    Standard,
+    // Each instance wins as many copies of each of the next cards as the card has matches
+    Proportional,
+    // Like Standard, but never reaching further than the given number of following cards
+    CappedReach(usize),
+    // Like Standard, but won copies only win further copies up to the given number of generations
+    CappedDepth(usize),
+}
+
+#[derive(Debug, PartialEq)]
+struct CascadeEntry {
+    id: u32,
+    matches: u32,
+    // Instances of the card, including the original
+    copies: u32,
+    // Ids of the cards copies were won from, together with the number of copies won from them
+    sources: Vec<(u32, u32)>,
+}
+
+// Simulates the cascade of the second puzzle under the given rule, recording where copies came from.
+// Fails with the id of the first card whose copies no longer fit into a u32.
+fn simulate_cascade(cards: &[Card], rule: WinRule) -> Result<Vec<CascadeEntry>, OverflowError> {
+    let mut entries = cards
+        .iter()
+        .map(|card| CascadeEntry {
+            id: card.id,
+            matches: count_matching_values(card),
+            copies: 0,
+            sources: Vec::new(),
+        })
+        .collect::<Vec<_>>();
+
+    // Instances per card, split by the generation in which they were won
+    let mut generations: Vec<Vec<u32>> = cards.iter().map(|_| Vec::from([1])).collect();
+
+    for card_index in 0..entries.len() {
+        let matches = entries[card_index].matches;
+        let (reach, copies_per_instance) = match rule {
+            WinRule::Standard | WinRule::CappedDepth(_) => (matches as usize, 1),
+            WinRule::Proportional => (matches as usize, matches),
+            WinRule::CappedReach(cap) => ((matches as usize).min(cap), 1),
+        };
+        let targets = card_index + 1..(card_index + 1 + reach).min(entries.len());
+
+        let card_id = entries[card_index].id;
+        let card_generations = generations[card_index].clone();
+        entries[card_index].copies = card_generations
+            .iter()
+            .try_fold(0u32, |copies, count| copies.checked_add(*count))
+            .ok_or(OverflowError { card_id })?;
+
+        for (generation, count) in card_generations.into_iter().enumerate() {
+            if matches!(rule, WinRule::CappedDepth(depth) if generation >= depth) {
+                continue;
+            }
+
+            for target_index in targets.clone() {
+                let target_id = entries[target_index].id;
+                let won = count
+                    .checked_mul(copies_per_instance)
+                    .ok_or(OverflowError { card_id })?;
+                let target_generations = &mut generations[target_index];
+
+                if target_generations.len() <= generation + 1 {
+                    target_generations.resize(generation + 2, 0);
+                }
+                target_generations[generation + 1] = target_generations[generation + 1]
+                    .checked_add(won)
+                    .ok_or(OverflowError { card_id: target_id })?;
+
+                let sources = &mut entries[target_index].sources;
+                match sources.last_mut() {
+                    Some((id, total)) if *id == card_id => {
+                        *total = total
+                            .checked_add(won)
+                            .ok_or(OverflowError { card_id: target_id })?
+                    }
+                    _ => sources.push((card_id, won)),
+                }
+            }
+        }
+    }
+
+    for entry in entries.iter_mut() {
+        entry.sources.retain(|(_, won)| *won > 0);
+    }
+
+    Ok(entries)
+}
+
+fn cascade_table(entries: &[CascadeEntry]) -> String {
+    let mut lines = Vec::from([format!(
+        "{:>6} | {:>7} | {:>10} | {}",
+        "Card", "Matches", "Copies", "Won from"
+    )]);
+
+    for entry in entries {
+        let sources = entry
+            .sources
+            .iter()
+            .map(|(id, won)| format!("{}x{}", id, won))
+            .collect::<Vec<_>>();
+
+        let line = format!(
+            "{:>6} | {:>7} | {:>10} | {}",
+            entry.id,
+            entry.matches,
+            entry.copies,
+            sources.join(", ")
+        );
+        lines.push(line.trim_end().to_string());
+    }
+
+    lines.push(format!(
+        "Total: {}",
+        entries
+            .iter()
+            .map(|entry| u64::from(entry.copies))
+            .sum::<u64>()
+    ));
+
+    lines.join("\n")
+}
+
+/*
+ * Prints the cascade as a tree where the children of a card are the cards it won copies of.
+ * Just like cargo tree, subtrees that were printed before are only marked with (*).
+ */
+fn cascade_tree(entries: &[CascadeEntry]) -> String {
+    fn print_subtree(
+        entries: &[CascadeEntry],
+        index: usize,
+        won: Option<u32>,
+        prefix: &str,
+        printed: &mut HashSet<usize>,
+        lines: &mut Vec<String>,
+    ) {
+        let entry = &entries[index];
+        let won = won.map_or(String::new(), |won| format!(" +{}", won));
+        let repeated = !printed.insert(index);
+
+        lines.push(format!(
+            "{}Card {}{} ({} copies){}",
+            prefix,
+            entry.id,
+            won,
+            entry.copies,
+            if repeated { " (*)" } else { "" }
+        ));
+
+        if repeated {
+            return;
+        }
+
+        let children = entries
+            .iter()
+            .enumerate()
+            .filter_map(|(child_index, child)| {
+                child
+                    .sources
+                    .iter()
+                    .find(|(id, _)| *id == entry.id)
+                    .map(|(_, won)| (child_index, *won))
+            })
+            .collect::<Vec<_>>();
+
+        let child_prefix = prefix.replace("├── ", "│   ").replace("└── ", "    ");
+        for (position, (child_index, won)) in children.iter().enumerate() {
+            let branch = if position + 1 == children.len() {
+                "└── "
+            } else {
+                "├── "
+            };
+
+            print_subtree(
+                entries,
+                *child_index,
+                Some(*won),
+                &format!("{}{}", child_prefix, branch),
+                printed,
+                lines,
+            );
+        }
+    }
+
+    let mut printed = HashSet::new();
+    let mut lines = Vec::new();
+
+    for index in 0..entries.len() {
+        if !printed.contains(&index) {
+            print_subtree(entries, index, None, "", &mut printed, &mut lines);
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn parse_win_rule(args: &[String]) -> Option<WinRule> {
+    match args {
+        [rule] if rule == "standard" => Some(WinRule::Standard),
+        [rule] if rule == "proportional" => Some(WinRule::Proportional),
+        [rule, cap] if rule == "reach" => Some(WinRule::CappedReach(cap.parse().ok()?)),
+        [rule, cap] if rule == "depth" => Some(WinRule::CappedDepth(cap.parse().ok()?)),
+        _ => None,
+    }
+}
+
+fn cascade(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let paths = ["./inputs/04/example-1.txt", "./inputs/04/input.txt"];
+    let usage =
+        "Usage: 4 big | <table|tree> <standard|proportional|reach <cards>|depth <generations>>";
+
+    let (format, rule) = args.split_first().ok_or(usage)?;
+    let rule = parse_win_rule(rule).ok_or(usage)?;
+
+    for path in paths {
+        println!("Reading file {}.", path);
+        let contents = fs::read_to_string(path)?;
+
+        let cards = contents.lines().flat_map(parse_card).collect::<Vec<_>>();
+        let entries = simulate_cascade(&cards, rule)?;
+
+        match format.as_str() {
+            "table" => println!("{}", cascade_table(&entries)),
+            "tree" => println!("{}", cascade_tree(&entries)),
+            _ => return Err(usage.into()),
+        }
+    }
+
+    Ok(())
+}
+
+// Both puzzles with arbitrary precision
+fn big() -> Result<(), Box<dyn Error>> {
+    let paths = ["./inputs/04/example-1.txt", "./inputs/04/input.txt"];
+
+    for path in paths {
+        println!("Reading file {}.", path);
+        let contents = fs::read_to_string(path)?;
+
+        let cards = contents.lines().flat_map(parse_card).collect::<Vec<_>>();
+
+        let mut sum = BigUint::default();
+        for card in cards.iter() {
+            sum += &big_card_value(card);
+        }
+
+        println!("Sum of card values: {}", sum);
+        println!("Number of cards won: {}", big_count_cards(&cards));
     }
 
     Ok(())
 }
 
 pub fn main() -> Result<(), Box<dyn Error>> {
+    let args = crate::task_args();
+    if args == ["big"] {
+        return big();
+    }
+    if !args.is_empty() {
+        return cascade(&args);
+    }
+
     println!("04-1:");
     first()?;
     println!("04-2:");
@@ -116,7 +443,24 @@ pub fn main() -> Result<(), Box<dyn Error>> {
 
 #[cfg(test)]
 mod tests {
-    use super::{card_value, parse_card, Card};
+    use super::{
+        big_card_value, big_count_cards, card_value, cascade_tree, count_cards, parse_card,
+        simulate_cascade, Card, OverflowError, WinRule,
+    };
+
+    fn example_cards() -> Vec<Card> {
+        [
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
+            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+        ]
+        .iter()
+        .flat_map(|line| parse_card(line))
+        .collect()
+    }
 
     #[test]
     fn parse_card_should_parse_the_first_example() {
@@ -141,6 +485,104 @@ mod tests {
             gots: [83, 86, 6, 31, 17, 9, 48, 53].to_vec(),
         };
 
-        assert_eq!(card_value(&card), 8)
+        assert_eq!(card_value(&card), Ok(8))
+    }
+
+    #[test]
+    fn simulate_cascade_should_match_count_cards() {
+        let cards = example_cards();
+        let entries = simulate_cascade(&cards, WinRule::Standard).unwrap();
+
+        let copies = entries.iter().map(|entry| entry.copies).collect::<Vec<_>>();
+
+        assert_eq!(copies, [1, 2, 4, 8, 14, 1].to_vec());
+        assert_eq!(Ok(copies.iter().sum::<u32>()), count_cards(cards));
+        assert_eq!(entries[3].sources, [(1, 1), (2, 2), (3, 4)].to_vec());
+    }
+
+    #[test]
+    fn simulate_cascade_should_apply_custom_rules() {
+        let cards = example_cards();
+
+        let copies = |rule| {
+            simulate_cascade(&cards, rule)
+                .unwrap()
+                .iter()
+                .map(|entry| entry.copies)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            copies(WinRule::Proportional),
+            [1, 5, 15, 45, 80, 1].to_vec()
+        );
+        assert_eq!(copies(WinRule::CappedReach(1)), [1, 2, 3, 4, 5, 1].to_vec());
+        assert_eq!(copies(WinRule::CappedDepth(1)), [1, 2, 3, 4, 4, 1].to_vec());
+    }
+
+    #[test]
+    fn cascade_tree_should_mark_repeated_subtrees() {
+        let entries = simulate_cascade(&example_cards(), WinRule::CappedReach(1)).unwrap();
+
+        let expected = [
+            "Card 1 (1 copies)",
+            "└── Card 2 +1 (2 copies)",
+            "    └── Card 3 +2 (3 copies)",
+            "        └── Card 4 +3 (4 copies)",
+            "            └── Card 5 +4 (5 copies)",
+            "Card 6 (1 copies)",
+        ]
+        .join("\n");
+
+        assert_eq!(cascade_tree(&entries), expected);
+    }
+
+    // Every card wins copies of all following cards, so card k ends up with 2^(k-1) instances
+    fn exploding_cards(count: u32) -> Vec<Card> {
+        (1..=count)
+            .map(|id| {
+                let numbers = (1..=count - id).collect::<Vec<_>>();
+
+                Card {
+                    id,
+                    wins: numbers.clone(),
+                    gots: numbers,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn card_value_should_report_overflow() {
+        let cards = exploding_cards(40);
+
+        assert_eq!(card_value(&cards[0]), Err(OverflowError { card_id: 1 }));
+        assert_eq!(
+            big_card_value(&cards[0]).to_string(),
+            (1_u64 << 38).to_string()
+        );
+    }
+
+    #[test]
+    fn count_cards_should_report_overflow() {
+        let cards = exploding_cards(40);
+
+        assert_eq!(
+            big_count_cards(&cards).to_string(),
+            ((1_u64 << 40) - 1).to_string()
+        );
+        assert_eq!(count_cards(cards), Err(OverflowError { card_id: 32 }));
+        assert_eq!(big_count_cards(&example_cards()).to_string(), "30");
+    }
+
+    #[test]
+    fn simulate_cascade_should_report_overflow() {
+        let cards = exploding_cards(20);
+
+        assert!(simulate_cascade(&cards, WinRule::Standard).is_ok());
+        assert_eq!(
+            simulate_cascade(&cards, WinRule::Proportional).err(),
+            Some(OverflowError { card_id: 9 })
+        );
     }
 }
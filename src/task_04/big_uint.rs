@@ -0,0 +1,120 @@
+use std::{fmt, ops::AddAssign};
+
+/*
+ * A minimal arbitrary precision unsigned integer.
+ * Limbs are stored least significant first in base 2^32 without trailing zeros,
+ * so that zero is the empty Vec and derived equality works.
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn power_of_two(exponent: u32) -> BigUint {
+        let mut limbs = vec![0; (exponent / 32) as usize];
+        limbs.push(1 << (exponent % 32));
+
+        BigUint { limbs }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    // Divides in place, returning the remainder
+    fn divide_by(&mut self, divisor: u32) -> u32 {
+        let mut remainder: u64 = 0;
+
+        for limb in self.limbs.iter_mut().rev() {
+            let current = (remainder << 32) | u64::from(*limb);
+            *limb = (current / u64::from(divisor)) as u32;
+            remainder = current % u64::from(divisor);
+        }
+
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+
+        remainder as u32
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(value: u32) -> BigUint {
+        if value == 0 {
+            return BigUint::default();
+        }
+
+        BigUint {
+            limbs: Vec::from([value]),
+        }
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, other: &BigUint) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+
+        let mut carry = false;
+        for (index, limb) in self.limbs.iter_mut().enumerate() {
+            let other_limb = other.limbs.get(index).copied().unwrap_or(0);
+            let (sum, overflowed) = limb.overflowing_add(other_limb);
+            let (sum, carried) = sum.overflowing_add(u32::from(carry));
+            *limb = sum;
+            carry = overflowed || carried;
+        }
+
+        if carry {
+            self.limbs.push(1);
+        }
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // Chunks of nine decimal digits, least significant first
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            chunks.push(rest.divide_by(1_000_000_000));
+        }
+
+        let mut chunks = chunks.iter().rev();
+        write!(f, "{}", chunks.next().unwrap_or(&0))?;
+        for chunk in chunks {
+            write!(f, "{:09}", chunk)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BigUint;
+
+    #[test]
+    fn add_assign_should_carry_across_limbs() {
+        let mut value = BigUint::from(u32::MAX);
+        value += &BigUint::from(1);
+
+        assert_eq!(value, BigUint::power_of_two(32));
+        assert_eq!(value.to_string(), "4294967296");
+    }
+
+    #[test]
+    fn display_should_print_large_powers_of_two() {
+        assert_eq!(BigUint::from(0).to_string(), "0");
+        assert_eq!(
+            BigUint::power_of_two(100).to_string(),
+            "1267650600228229401496703205376"
+        );
+    }
+}
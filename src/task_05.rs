@@ -1,5 +1,9 @@
 use regex::Regex;
-use std::{error::Error, fs};
+use std::{
+    collections::{HashMap, VecDeque},
+    error::Error,
+    fmt, fs,
+};
 
 #[derive(Debug, PartialEq)]
 struct CategoryMap {
@@ -67,134 +71,353 @@ fn parse_task_input(input: String) -> Option<TaskInput> {
     })
 }
 
-fn map_seed(seed: i64, category_map: &CategoryMap) -> i64 {
-    let mapping = category_map
-        .mappings
-        .iter()
-        .filter(|(_destination, source, length)| seed >= *source && seed < *source + *length)
-        .next();
+/*
+ * A piecewise linear function with slope one.
+ * The pieces are sorted, contiguous and cover all of i64, each adding its offset to values in [start, end).
+ * The first and last piece always have an offset of zero, so that no arithmetic happens near the bounds of i64.
+ */
+#[derive(Clone, Debug, PartialEq)]
+struct Piece {
+    start: i64,
+    end: i64,
+    offset: i64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct PiecewiseMap {
+    pieces: Vec<Piece>,
+}
+
+impl PiecewiseMap {
+    fn identity() -> PiecewiseMap {
+        PiecewiseMap {
+            pieces: Vec::from([Piece {
+                start: i64::MIN,
+                end: i64::MAX,
+                offset: 0,
+            }]),
+        }
+    }
+
+    // Builds pieces from (start, end, offset) triples, filling holes with the identity and merging neighbours
+    fn from_intervals(mut intervals: Vec<(i64, i64, i64)>) -> PiecewiseMap {
+        intervals.sort();
+
+        let mut pieces: Vec<Piece> = Vec::new();
+        let mut push = |start: i64, end: i64, offset: i64| {
+            if start >= end {
+                return;
+            }
+
+            match pieces.last_mut() {
+                Some(last) if last.offset == offset && last.end == start => last.end = end,
+                _ => pieces.push(Piece { start, end, offset }),
+            }
+        };
+
+        let mut position = i64::MIN;
+        for (start, end, offset) in intervals {
+            push(position, start, 0);
+            push(start.max(position), end, offset);
+            position = position.max(end);
+        }
+        push(position, i64::MAX, 0);
+
+        PiecewiseMap { pieces }
+    }
+
+    fn index_of(&self, value: i64) -> usize {
+        self.pieces
+            .partition_point(|piece| piece.end <= value)
+            .min(self.pieces.len() - 1)
+    }
+
+    fn apply(&self, value: i64) -> i64 {
+        value + self.pieces[self.index_of(value)].offset
+    }
+
+    // The map that first applies self and then next
+    fn then(&self, next: &PiecewiseMap) -> PiecewiseMap {
+        let mut intervals = Vec::new();
+
+        for piece in self.pieces.iter() {
+            let image_start = piece.start + piece.offset;
+            let image_end = piece.end + piece.offset;
+
+            for next_piece in next.pieces[next.index_of(image_start)..].iter() {
+                if next_piece.start >= image_end {
+                    break;
+                }
+
+                intervals.push((
+                    image_start.max(next_piece.start) - piece.offset,
+                    image_end.min(next_piece.end) - piece.offset,
+                    piece.offset + next_piece.offset,
+                ));
+            }
+        }
+
+        PiecewiseMap::from_intervals(intervals)
+    }
+
+    // The inverse map, if the map is a bijection
+    fn invert(&self) -> Option<PiecewiseMap> {
+        let mut images = self
+            .pieces
+            .iter()
+            .map(|piece| {
+                (
+                    piece.start + piece.offset,
+                    piece.end + piece.offset,
+                    -piece.offset,
+                )
+            })
+            .collect::<Vec<_>>();
+        images.sort();
+
+        let mut position = i64::MIN;
+        for (start, end, _) in images.iter() {
+            if *start != position {
+                return None;
+            }
+            position = *end;
+        }
+
+        Some(PiecewiseMap::from_intervals(images))
+    }
+
+    // All intervals of inputs that the map sends into [start, end), sorted and merged
+    fn preimage(&self, start: i64, end: i64) -> Vec<(i64, i64)> {
+        let mut intervals = self
+            .pieces
+            .iter()
+            .filter_map(|piece| {
+                let from = (piece.start + piece.offset).max(start);
+                let to = (piece.end + piece.offset).min(end);
+
+                if from < to {
+                    Some((from - piece.offset, to - piece.offset))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        intervals.sort();
+
+        let mut merged: Vec<(i64, i64)> = Vec::new();
+        for (from, to) in intervals {
+            match merged.last_mut() {
+                Some((_, last_to)) if *last_to >= from => *last_to = (*last_to).max(to),
+                _ => merged.push((from, to)),
+            }
+        }
+
+        merged
+    }
+
+    // The smallest value the map takes for inputs in [start, end)
+    fn minimum(&self, start: i64, end: i64) -> Option<i64> {
+        self.pieces[self.index_of(start)..]
+            .iter()
+            .take_while(|piece| piece.start < end)
+            .map(|piece| piece.start.max(start) + piece.offset)
+            .min()
+    }
+}
 
-    match mapping {
-        None => seed,
-        Some((destination, source, _length)) => destination + (seed - source),
+impl From<&CategoryMap> for PiecewiseMap {
+    fn from(category_map: &CategoryMap) -> PiecewiseMap {
+        PiecewiseMap::from_intervals(
+            category_map
+                .mappings
+                .iter()
+                .map(|(destination, source, length)| {
+                    (*source, source + length, destination - source)
+                })
+                .collect(),
+        )
     }
 }
 
-fn map_seeds(seeds: Vec<i64>, category_map: &CategoryMap) -> Vec<i64> {
-    seeds
-        .iter()
-        .map(|seed| map_seed(*seed, category_map))
-        .collect()
+#[derive(Debug, PartialEq)]
+enum AlmanacError {
+    UnknownCategory(String),
+    MissingLink { from: String, to: String },
 }
 
-pub fn first() -> Result<(), Box<dyn Error>> {
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlmanacError::UnknownCategory(category) => {
+                write!(f, "No map mentions the category {}", category)
+            }
+            AlmanacError::MissingLink { from, to } => {
+                write!(f, "No chain of maps leads from {} to {}", from, to)
+            }
+        }
+    }
+}
+
+impl Error for AlmanacError {}
+
+/*
+ * Finds the category maps that convert from one category to another.
+ * The maps form a graph between categories, so they may be given in any order.
+ * When several chains of maps lead to the target the shortest one is used,
+ * preferring maps that were given earlier.
+ */
+fn find_path<'a>(
+    task_input: &'a TaskInput,
+    from: &str,
+    to: &str,
+) -> Result<Vec<&'a CategoryMap>, AlmanacError> {
+    let known = |category: &str| {
+        category == "seed"
+            || task_input
+                .category_maps
+                .iter()
+                .any(|category_map| category_map.from == category || category_map.to == category)
+    };
+
+    for category in [from, to] {
+        if !known(category) {
+            return Err(AlmanacError::UnknownCategory(category.to_string()));
+        }
+    }
+
+    // For each reached category the map it was first reached with
+    let mut reached_by: HashMap<&str, Option<&CategoryMap>> = HashMap::from([(from, None)]);
+    let mut queue = VecDeque::from([from]);
+
+    while let Some(current) = queue.pop_front() {
+        if current == to {
+            break;
+        }
+
+        for category_map in task_input.category_maps.iter() {
+            if category_map.from == current && !reached_by.contains_key(category_map.to.as_str()) {
+                reached_by.insert(&category_map.to, Some(category_map));
+                queue.push_back(&category_map.to);
+            }
+        }
+    }
+
+    let mut path = Vec::new();
+    let mut current = to;
+
+    loop {
+        match reached_by.get(current) {
+            None => {
+                return Err(AlmanacError::MissingLink {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                })
+            }
+            Some(None) => break,
+            Some(Some(category_map)) => {
+                path.push(*category_map);
+                current = &category_map.from;
+            }
+        }
+    }
+
+    path.reverse();
+    Ok(path)
+}
+
+// Composes the maps converting from one category to another into a single map
+fn compile(task_input: &TaskInput, from: &str, to: &str) -> Result<PiecewiseMap, AlmanacError> {
+    Ok(find_path(task_input, from, to)?
+        .into_iter()
+        .fold(PiecewiseMap::identity(), |map, category_map| {
+            map.then(&PiecewiseMap::from(category_map))
+        }))
+}
+
+fn query(args: &[String]) -> Result<(), Box<dyn Error>> {
     let paths = ["./inputs/05/example-1.txt", "./inputs/05/input.txt"];
+    let usage = "Usage: 5 seeds <category> <start> <length> | seed <category> <value> \
+        | convert <from> <to> <value> | path <from> <to>";
 
     for path in paths {
         println!("Reading file {}", path);
 
         let contents = fs::read_to_string(path)?;
-        let task_input = parse_task_input(contents).unwrap();
+        let task_input = parse_task_input(contents).ok_or("Could not parse input")?;
 
-        let locations = task_input
-            .category_maps
-            .iter()
-            .fold(task_input.seeds, |seeds, category_map| {
-                map_seeds(seeds, category_map)
-            });
+        match args {
+            [command, category, start, length] if command == "seeds" => {
+                let start = start.parse::<i64>()?;
+                let end = start + length.parse::<i64>()?;
 
-        let lowest_location = locations.iter().min().unwrap_or(&0);
+                let seed_ranges = compile(&task_input, "seed", category)?.preimage(start, end);
+                if seed_ranges.is_empty() {
+                    println!("No seeds lead there");
+                }
 
-        println!("Lowest location: {}", lowest_location);
+                for (from, to) in seed_ranges {
+                    println!("Seeds {} to {} (length {})", from, to - 1, to - from);
+                }
+            }
+            [command, category, value] if command == "seed" => {
+                let value = value.parse::<i64>()?;
+                let map = compile(&task_input, "seed", category)?;
+
+                // A bijection has exactly one seed per value
+                match map.invert() {
+                    Some(inverse) => println!("Seed {}", inverse.apply(value)),
+                    None => println!("Seeds {:?}", map.preimage(value, value + 1)),
+                }
+            }
+            [command, from, to, value] if command == "convert" => {
+                let value = value.parse::<i64>()?;
+
+                println!("{} {}", to, compile(&task_input, from, to)?.apply(value));
+            }
+            [command, from, to] if command == "path" => {
+                let categories = find_path(&task_input, from, to)?
+                    .iter()
+                    .map(|category_map| category_map.to.as_str())
+                    .collect::<Vec<_>>();
+
+                println!(
+                    "{}",
+                    [from.as_str()]
+                        .iter()
+                        .chain(categories.iter())
+                        .copied()
+                        .collect::<Vec<_>>()
+                        .join(" -> ")
+                );
+            }
+            _ => return Err(usage.into()),
+        }
     }
 
     Ok(())
 }
 
-#[allow(dead_code)]
-fn map_seed_range(seed_range: (i64, i64), category_map: &CategoryMap) -> Vec<(i64, i64)> {
-    let mappings = &mut category_map.mappings.clone();
-    mappings.sort_by(|(_, source_a, _), (_, source_b, _)| source_a.cmp(source_b));
-
-    let (mut seed_start, mut seed_length) = seed_range;
-
-    let mut mapped_ranges: Vec<(i64, i64)> = mappings
-        .iter()
-        .flat_map(
-            |(destination, source_start, mapping_length)| -> Vec<(i64, i64)> {
-                let seed_end = seed_start + seed_length - 1;
-                let source_end = source_start + mapping_length - 1;
+pub fn first() -> Result<(), Box<dyn Error>> {
+    let paths = ["./inputs/05/example-1.txt", "./inputs/05/input.txt"];
 
-                // No seed range present to map
-                if seed_length <= 0 {
-                    return Vec::new();
-                }
+    for path in paths {
+        println!("Reading file {}", path);
 
-                // Seed range entirely later than current mapping
-                if seed_start > source_end {
-                    return Vec::new();
-                }
+        let contents = fs::read_to_string(path)?;
+        let task_input = parse_task_input(contents).unwrap();
+        let seed_to_location = compile(&task_input, "seed", "location")?;
 
-                // Seed range entirely before source
-                if seed_end < *source_start {
-                    let result = [(seed_start, seed_length)].to_vec();
-                    seed_length = 0;
-                    return result;
-                }
+        let locations = task_input
+            .seeds
+            .iter()
+            .map(|seed| seed_to_location.apply(*seed));
 
-                let seed_starts_before_mapping = seed_start < *source_start;
-                let seed_ends_after_mapping = seed_end > source_end;
-
-                match (seed_starts_before_mapping, seed_ends_after_mapping) {
-                    (true, true) => {
-                        // seed range overlapping mapping entirely
-                        let before_length = source_start - seed_start;
-                        let before_mapping = (seed_start, before_length);
-                        seed_length -= before_length;
-
-                        let inner_mapping = (*destination, *mapping_length);
-                        seed_start = source_start + mapping_length;
-                        seed_length -= mapping_length;
-
-                        return [before_mapping, inner_mapping].to_vec();
-                    }
-                    (true, false) => {
-                        // seed range precedes mapping range, but doesn't continue after
-                        let before_length = source_start - seed_start;
-                        let before_mapping = (seed_start, before_length);
-                        seed_length -= before_length;
-
-                        let inner_mapping = (*destination, seed_length);
-                        seed_length = 0;
-
-                        return [before_mapping, inner_mapping].to_vec();
-                    }
-                    (false, true) => {
-                        // seed range starts inside mapping range and continues after
-
-                        let inner_length = mapping_length - (seed_start - *source_start);
-                        let inner_mapping = (*destination, inner_length);
-                        seed_length -= inner_length;
-
-                        return [inner_mapping].to_vec();
-                    }
-                    (false, false) => {
-                        // seed range entirely contained
-                        let result = [(seed_start, seed_length)].to_vec();
-                        seed_length = 0;
-
-                        return result;
-                    }
-                }
-            },
-        )
-        .collect();
+        let lowest_location = locations.min().unwrap_or(0);
 
-    if seed_length > 0 {
-        mapped_ranges.push((seed_start, seed_length))
+        println!("Lowest location: {}", lowest_location);
     }
 
-    return mapped_ranges;
+    Ok(())
 }
 
 pub fn second() -> Result<(), Box<dyn Error>> {
@@ -205,6 +428,7 @@ pub fn second() -> Result<(), Box<dyn Error>> {
 
         let contents = fs::read_to_string(path)?;
         let task_input = parse_task_input(contents).unwrap();
+        let seed_to_location = compile(&task_input, "seed", "location")?;
 
         let seed_chunks = task_input
             .seeds
@@ -217,26 +441,23 @@ pub fn second() -> Result<(), Box<dyn Error>> {
                 Some((*a, *b))
             });
 
-        let seeds = seed_chunks
-            .flat_map(|(from, length)| -> Vec<i64> { (from..from + length).collect() })
-            .collect::<Vec<_>>();
-
-        let locations = task_input
-            .category_maps
-            .iter()
-            .fold(seeds, |seeds, category_map| map_seeds(seeds, category_map));
-
-        let lowest_location = locations.iter().min().unwrap_or(&0);
+        let lowest_location = seed_chunks
+            .filter_map(|(from, length)| seed_to_location.minimum(from, from + length))
+            .min()
+            .unwrap_or(0);
 
         println!("lowest valid location: {}", lowest_location);
-
-        break;
     }
 
     Ok(())
 }
 
 pub fn main() -> Result<(), Box<dyn Error>> {
+    let args = crate::task_args();
+    if !args.is_empty() {
+        return query(&args);
+    }
+
     println!("05-1:");
     first()?;
     println!("05-2:");
@@ -245,63 +466,174 @@ pub fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use crate::task_05::{map_seed, map_seed_range};
-
-//     use super::CategoryMap;
-
-//     #[test]
-//     fn map_seed_should_behave() {
-//         let category_map = &CategoryMap {
-//             from: "seed".to_string(),
-//             to: "soil".to_string(),
-//             mappings: [(50, 98, 2), (52, 50, 48)].to_vec(),
-//         };
-
-//         assert_eq!(map_seed(79, category_map), 81);
-//         assert_eq!(map_seed(14, category_map), 14);
-//         assert_eq!(map_seed(55, category_map), 57);
-//         assert_eq!(map_seed(13, category_map), 13);
-//     }
-
-//     #[test]
-//     fn map_seed_range_should_behave() {
-//         let category_map = &CategoryMap {
-//             from: "seed".to_string(),
-//             to: "soil".to_string(),
-//             mappings: [(10, 3, 2), (10, 7, 2)].to_vec(),
-//         };
-
-//         // seed before both mappings
-//         assert_eq!(map_seed_range((0, 2), category_map), [(0, 2)].to_vec());
-//         // seed between both mappings
-//         assert_eq!(map_seed_range((5, 2), category_map), [(5, 2)].to_vec());
-//         // seed after both mappings
-//         assert_eq!(map_seed_range((9, 2), category_map), [(9, 2)].to_vec());
-
-//         // seed before and in first mapping
-//         assert_eq!(
-//             map_seed_range((0, 3), category_map),
-//             [(0, 2), (3, 1)].to_vec()
-//         );
-//         // // seed entirely in first mapping
-//         // assert_eq!(map_seed_range((), category_map), [()].to_vec());
-//         // // seed in and after first mapping
-//         // assert_eq!(map_seed_range((), category_map), [()].to_vec());
-//         // // seed spanning all mappings
-//         // assert_eq!(map_seed_range((), category_map), [()].to_vec());
-//     }
-
-//     // #[test]
-//     // fn map_seed_range_should_work_like_example() {
-//     //     let category_map = &CategoryMap {
-//     //         from: "seed".to_string(),
-//     //         to: "soil".to_string(),
-//     //         mappings: [(50, 98, 2), (52, 50, 48)].to_vec(),
-//     //     };
-
-//     //     assert_eq!(map_seed_range((79, 14), category_map), [(79, 14)].to_vec());
-//     //     assert_eq!(map_seed_range((55, 13), category_map), [(57, 13)].to_vec());
-//     // }
-// }
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, fs};
+
+    use super::{compile, find_path, parse_task_input, AlmanacError, CategoryMap, PiecewiseMap};
+
+    #[test]
+    fn piecewise_map_should_map_like_the_example() {
+        let category_map = &CategoryMap {
+            from: "seed".to_string(),
+            to: "soil".to_string(),
+            mappings: [(50, 98, 2), (52, 50, 48)].to_vec(),
+        };
+        let map = PiecewiseMap::from(category_map);
+
+        assert_eq!(map.apply(79), 81);
+        assert_eq!(map.apply(14), 14);
+        assert_eq!(map.apply(55), 57);
+        assert_eq!(map.apply(13), 13);
+        assert_eq!(map.apply(98), 50);
+        assert_eq!(map.apply(100), 100);
+        assert_eq!(map.minimum(45, 60), Some(45));
+        assert_eq!(map.minimum(90, 100), Some(50));
+    }
+
+    #[test]
+    fn compile_should_compose_all_category_maps() -> Result<(), Box<dyn Error>> {
+        let contents = fs::read_to_string("./inputs/05/example-1.txt")?;
+        let task_input = parse_task_input(contents).unwrap();
+        let map = compile(&task_input, "seed", "location")?;
+
+        let locations = task_input
+            .seeds
+            .iter()
+            .map(|seed| map.apply(*seed))
+            .collect::<Vec<_>>();
+
+        assert_eq!(locations, [82, 43, 86, 35].to_vec());
+        assert_eq!(map.minimum(79, 79 + 14), Some(46));
+        assert_eq!(map.minimum(55, 55 + 13), Some(56));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invert_should_undo_bijective_maps() -> Result<(), Box<dyn Error>> {
+        let contents = fs::read_to_string("./inputs/05/example-1.txt")?;
+        let task_input = parse_task_input(contents).unwrap();
+        let map = compile(&task_input, "seed", "location")?;
+        let inverse = map.invert().unwrap();
+
+        for seed in 0..200 {
+            assert_eq!(inverse.apply(map.apply(seed)), seed);
+        }
+        assert_eq!(map.then(&inverse), PiecewiseMap::identity());
+
+        let overlapping = PiecewiseMap::from_intervals([(0, 10, 5)].to_vec());
+        assert_eq!(overlapping.invert(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn preimage_should_find_originating_seeds() -> Result<(), Box<dyn Error>> {
+        let contents = fs::read_to_string("./inputs/05/example-1.txt")?;
+        let task_input = parse_task_input(contents).unwrap();
+        let seed_ranges_for = |category, start, end| {
+            compile(&task_input, "seed", category).map(|map| map.preimage(start, end))
+        };
+
+        assert_eq!(seed_ranges_for("soil", 81, 82), Ok([(79, 80)].to_vec()));
+        assert_eq!(seed_ranges_for("location", 46, 47), Ok([(82, 83)].to_vec()));
+        assert_eq!(seed_ranges_for("seed", 3, 7), Ok([(3, 7)].to_vec()));
+        assert_eq!(
+            seed_ranges_for("unknown", 0, 1),
+            Err(AlmanacError::UnknownCategory("unknown".to_string()))
+        );
+
+        let map = compile(&task_input, "seed", "location")?;
+        for (from, to) in seed_ranges_for("location", 0, 50).unwrap() {
+            for seed in from..to {
+                assert!(map.apply(seed) < 50);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn preimage_should_collect_all_overlapping_pieces() {
+        // Both 0..10 and 5..15 end up in 5..15
+        let map = PiecewiseMap::from_intervals([(0, 10, 5)].to_vec());
+
+        assert_eq!(map.preimage(5, 15), [(0, 15)].to_vec());
+        assert_eq!(map.preimage(12, 13), [(7, 8), (12, 13)].to_vec());
+    }
+
+    #[test]
+    fn compile_should_accept_maps_in_any_order() -> Result<(), Box<dyn Error>> {
+        let contents = fs::read_to_string("./inputs/05/example-1.txt")?;
+        let mut task_input = parse_task_input(contents).unwrap();
+        let expected = compile(&task_input, "seed", "location")?;
+
+        task_input.category_maps.reverse();
+
+        assert_eq!(compile(&task_input, "seed", "location")?, expected);
+        assert_eq!(compile(&task_input, "water", "light")?.apply(81), 74);
+        assert_eq!(find_path(&task_input, "light", "humidity")?.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_path_should_report_missing_links() -> Result<(), Box<dyn Error>> {
+        let contents = fs::read_to_string("./inputs/05/example-1.txt")?;
+        let mut task_input = parse_task_input(contents).unwrap();
+
+        assert_eq!(
+            find_path(&task_input, "location", "seed"),
+            Err(AlmanacError::MissingLink {
+                from: "location".to_string(),
+                to: "seed".to_string()
+            })
+        );
+
+        task_input
+            .category_maps
+            .retain(|category_map| category_map.from != "water");
+
+        assert!(find_path(&task_input, "seed", "location").is_err());
+        assert!(find_path(&task_input, "light", "location").is_ok());
+
+        // A shortcut is preferred over the longer chain
+        task_input.category_maps.push(CategoryMap {
+            from: "seed".to_string(),
+            to: "light".to_string(),
+            mappings: Vec::new(),
+        });
+
+        assert_eq!(find_path(&task_input, "seed", "location")?.len(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_path_should_stop_on_cycles() -> Result<(), Box<dyn Error>> {
+        let contents = fs::read_to_string("./inputs/05/example-1.txt")?;
+        let mut task_input = parse_task_input(contents).unwrap();
+
+        // Water and light now only lead back to each other
+        task_input
+            .category_maps
+            .retain(|category_map| category_map.from != "light");
+        task_input.category_maps.push(CategoryMap {
+            from: "light".to_string(),
+            to: "water".to_string(),
+            mappings: Vec::new(),
+        });
+
+        assert_eq!(
+            compile(&task_input, "seed", "location"),
+            Err(AlmanacError::MissingLink {
+                from: "seed".to_string(),
+                to: "location".to_string()
+            })
+        );
+        assert_eq!(find_path(&task_input, "water", "light")?.len(), 1);
+
+        Ok(())
+    }
+}
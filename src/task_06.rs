@@ -2,13 +2,13 @@ use std::{error::Error, fs};
 
 #[derive(Debug)]
 struct Race {
-    time: i64,
-    distance: i64,
+    time: i128,
+    distance: i128,
 }
 
 fn parse_input_1(contents: String) -> Vec<Race> {
     let lines = contents.lines().collect::<Vec<_>>();
-    let [line1, line2] = lines.as_slice() else {
+    let [line1, line2, ..] = lines.as_slice() else {
         return Vec::new();
     };
 
@@ -18,10 +18,10 @@ fn parse_input_1(contents: String) -> Vec<Race> {
 
     let times = line1
         .split(" ")
-        .filter_map(|token| token.parse::<i64>().ok());
+        .filter_map(|token| token.parse::<i128>().ok());
     let distances = line2
         .split(" ")
-        .filter_map(|token| token.parse::<i64>().ok());
+        .filter_map(|token| token.parse::<i128>().ok());
 
     times
         .zip(distances)
@@ -29,19 +29,34 @@ fn parse_input_1(contents: String) -> Vec<Race> {
         .collect()
 }
 
-fn count_ways_to_beat(race: &Race) -> i64 {
-    let mut count = 0;
+/*
+ * Holding for h ms beats the race when h * (time - h) > distance,
+ * which holds strictly between the roots of h² - time * h + distance.
+ * The roots are (time ± √(time² - 4 * distance)) / 2, so an integer square root
+ * narrows the first winning hold time down to three candidates.
+ */
+fn count_ways_to_beat(race: &Race) -> i128 {
+    let beats = |hold: i128| hold * (race.time - hold) > race.distance;
 
-    for speed in 0..race.time {
-        let remaining_time = race.time - speed;
-        let distance = remaining_time * speed;
-
-        if distance > race.distance {
-            count += 1;
-        }
+    let discriminant = race.time * race.time - 4 * race.distance;
+    if discriminant <= 0 {
+        return 0;
     }
 
-    return count;
+    // √discriminant lies in [root, root + 1), so the first hold past the lower root is one of these
+    let root = discriminant.isqrt();
+    let lowest = (race.time - root - 1).div_euclid(2);
+    let Some(first_win) = (lowest..=lowest + 2).find(|hold| beats(*hold)) else {
+        // No whole hold time lies strictly between the roots
+        return 0;
+    };
+
+    // Against a negative record even a boat that never moves wins, yet holds start at 0
+    let first_win = first_win.max(0);
+    // Winning hold times are symmetric around time / 2
+    let last_win = race.time - first_win;
+
+    last_win - first_win + 1
 }
 
 fn first() -> Result<(), Box<dyn Error>> {
@@ -51,7 +66,7 @@ fn first() -> Result<(), Box<dyn Error>> {
         let contents = fs::read_to_string(path)?;
         let races = parse_input_1(contents);
 
-        let solution = races.iter().map(count_ways_to_beat).product::<i64>();
+        let solution = races.iter().map(count_ways_to_beat).product::<i128>();
         println!("Product of winnable races: {}", solution);
     }
 
@@ -61,8 +76,9 @@ fn first() -> Result<(), Box<dyn Error>> {
 fn parse_input_2(contents: String) -> Option<Race> {
     let lines = contents
         .lines()
+        .filter(|line| line.starts_with("Time:") || line.starts_with("Distance:"))
         .map(|line| line.chars().filter(|c| c.is_digit(10)).collect::<String>())
-        .filter_map(|digits| digits.parse::<i64>().ok())
+        .filter_map(|digits| digits.parse::<i128>().ok())
         .collect::<Vec<_>>();
 
     let [time, distance] = lines.as_slice() else {
@@ -89,7 +105,206 @@ fn second() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+// How holding the button translates into speed
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RaceModel {
+    // Each ms held adds rate mm/ms of speed, the puzzle uses a rate of 1
+    LinearCharge { rate: i128 },
+    // Like LinearCharge, but the speed never exceeds max_speed
+    CappedSpeed { rate: i128, max_speed: i128 },
+    // The first ms held adds rate mm/ms of speed, each following ms adds decay less than the one before
+    DiminishingAcceleration { rate: i128, decay: i128 },
+}
+
+impl RaceModel {
+    fn speed(&self, hold: i128) -> i128 {
+        match *self {
+            RaceModel::LinearCharge { rate } => rate * hold,
+            RaceModel::CappedSpeed { rate, max_speed } => (rate * hold).min(max_speed),
+            RaceModel::DiminishingAcceleration { rate, decay } => {
+                // Only the first accelerating_ms add any speed
+                let accelerating_ms = if decay > 0 {
+                    (rate + decay - 1) / decay
+                } else {
+                    hold
+                };
+                let ms = hold.min(accelerating_ms);
+
+                ms * rate - decay * ms * (ms - 1) / 2
+            }
+        }
+    }
+
+    fn distance(&self, race: &Race, hold: i128) -> i128 {
+        self.speed(hold) * (race.time - hold)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct RaceAnalysis {
+    // First and last winning hold time
+    winning_holds: Option<(i128, i128)>,
+    optimal_hold: i128,
+    optimal_distance: i128,
+    // How far the optimal hold beats the record by
+    margin: i128,
+}
+
+/*
+ * The speed of every model is non-negative and concave in the hold time, and so is the remaining time.
+ * Their product, the distance, is therefore log-concave and thus unimodal,
+ * so binary searches find the optimum and the bounds of the single winning interval.
+ * Where the boat stands still the distance is flat instead of rising, which the search would take for the peak,
+ * so the search starts at the first hold that moves the boat, and a boat that never moves is optimal at 0.
+ */
+fn analyse_race(race: &Race, model: &RaceModel) -> RaceAnalysis {
+    let distance = |hold: i128| model.distance(race, hold);
+
+    // First hold in [low, high) where predicate holds, given it is monotone over that range
+    let partition_point = |mut low: i128, mut high: i128, predicate: &dyn Fn(i128) -> bool| {
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if predicate(middle) {
+                high = middle;
+            } else {
+                low = middle + 1;
+            }
+        }
+
+        low
+    };
+
+    let moving = partition_point(0, race.time, &|hold| distance(hold) > 0);
+    let optimal_hold = if moving == race.time {
+        0
+    } else {
+        partition_point(moving, race.time, &|hold| {
+            distance(hold) >= distance(hold + 1)
+        })
+    };
+    let optimal_distance = distance(optimal_hold);
+
+    let winning_holds = if optimal_distance > race.distance {
+        let first = partition_point(0, optimal_hold, &|hold| distance(hold) > race.distance);
+        let last = partition_point(optimal_hold, race.time + 1, &|hold| {
+            distance(hold) <= race.distance
+        }) - 1;
+
+        Some((first, last))
+    } else {
+        None
+    };
+
+    RaceAnalysis {
+        winning_holds,
+        optimal_hold,
+        optimal_distance,
+        margin: optimal_distance - race.distance,
+    }
+}
+
+fn parse_model(tokens: &[&str]) -> Option<RaceModel> {
+    let numbers = tokens[1..]
+        .iter()
+        .map(|token| token.parse::<i128>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    // Negative parameters would make the speed negative or let it grow ever faster
+    if numbers.iter().any(|number| *number < 0) {
+        return None;
+    }
+
+    match (*tokens.first()?, numbers.as_slice()) {
+        ("linear", [rate]) => Some(RaceModel::LinearCharge { rate: *rate }),
+        ("capped", [rate, max_speed]) => Some(RaceModel::CappedSpeed {
+            rate: *rate,
+            max_speed: *max_speed,
+        }),
+        ("diminishing", [rate, decay]) => Some(RaceModel::DiminishingAcceleration {
+            rate: *rate,
+            decay: *decay,
+        }),
+        _ => None,
+    }
+}
+
+/*
+ * Reads per race models from an optional line like
+ * Model: linear:2 capped:1:5 diminishing:4:1
+ * Races without a model use the LinearCharge of the puzzle.
+ */
+fn parse_models(contents: &str, race_count: usize) -> Option<Vec<RaceModel>> {
+    let default = RaceModel::LinearCharge { rate: 1 };
+
+    let Some(line) = contents.lines().find(|line| line.starts_with("Model:")) else {
+        return Some(vec![default; race_count]);
+    };
+
+    let mut models = line["Model:".len()..]
+        .split_whitespace()
+        .map(|token| parse_model(&token.split(':').collect::<Vec<_>>()))
+        .collect::<Option<Vec<_>>>()?;
+    models.resize(race_count.max(models.len()), default);
+
+    Some(models)
+}
+
+fn analyse(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let paths = ["./inputs/06/example-1.txt", "./inputs/06/input.txt"];
+    let usage =
+        "Usage: 6 analyse [linear <rate> | capped <rate> <max speed> | diminishing <rate> <decay>]";
+
+    let model = match args {
+        [command] if command == "analyse" => None,
+        [command, model @ ..] if command == "analyse" => Some(
+            parse_model(&model.iter().map(|arg| arg.as_str()).collect::<Vec<_>>()).ok_or(usage)?,
+        ),
+        _ => return Err(usage.into()),
+    };
+
+    for path in paths {
+        println!("Reading file {}", path);
+        let contents = fs::read_to_string(path)?;
+
+        let races = parse_input_1(contents.clone());
+        let models = match model {
+            Some(model) => vec![model; races.len()],
+            None => parse_models(&contents, races.len()).ok_or("Could not parse models")?,
+        };
+
+        for (race, model) in races.iter().zip(models.iter()) {
+            let analysis = analyse_race(race, model);
+            let winning = match analysis.winning_holds {
+                Some((first, last)) => format!(
+                    "win holding {}..={} ms ({} ways)",
+                    first,
+                    last,
+                    last - first + 1
+                ),
+                None => "cannot be won".to_string(),
+            };
+
+            println!(
+                "Race of {} ms, record {} mm, {:?}: {}, best hold {} ms for {} mm, margin {} mm",
+                race.time,
+                race.distance,
+                model,
+                winning,
+                analysis.optimal_hold,
+                analysis.optimal_distance,
+                analysis.margin
+            );
+        }
+    }
+
+    Ok(())
+}
+
 pub fn main() -> Result<(), Box<dyn Error>> {
+    let args = crate::task_args();
+    if !args.is_empty() {
+        return analyse(&args);
+    }
+
     println!("06-1:");
     first()?;
     println!("06-2:");
@@ -97,3 +312,188 @@ pub fn main() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::random::Lcg;
+
+    use super::{
+        analyse_race, count_ways_to_beat, parse_input_1, parse_input_2, parse_models, Race,
+        RaceAnalysis, RaceModel,
+    };
+
+    // Tries every hold time, as the closed form solution should agree with it
+    fn count_ways_to_beat_by_looping(race: &Race) -> i128 {
+        (0..=race.time)
+            .filter(|hold| (race.time - hold) * hold > race.distance)
+            .count() as i128
+    }
+
+    #[test]
+    fn count_ways_to_beat_should_match_the_example() {
+        let contents = "Time:      7  15   30\nDistance:  9  40  200".to_string();
+
+        let ways = parse_input_1(contents.clone())
+            .iter()
+            .map(count_ways_to_beat)
+            .collect::<Vec<_>>();
+
+        assert_eq!(ways, [4, 8, 9].to_vec());
+        assert_eq!(
+            parse_input_2(contents).map(|race| count_ways_to_beat(&race)),
+            Some(71503)
+        );
+    }
+
+    #[test]
+    fn count_ways_to_beat_should_agree_with_looping_on_random_races() {
+        let mut random = Lcg::new(2023);
+
+        for _ in 0..2000 {
+            let time = random.below(300) as i128;
+            // Including a few negative records
+            let distance = random.below((time * time / 4 + 12) as u64) as i128 - 10;
+            let race = Race { time, distance };
+
+            assert_eq!(
+                count_ways_to_beat(&race),
+                count_ways_to_beat_by_looping(&race),
+                "{:?}",
+                race
+            );
+        }
+    }
+
+    #[test]
+    fn count_ways_to_beat_should_handle_huge_races() {
+        let time: i128 = 10_i128.pow(18);
+        let race = Race { time, distance: 0 };
+
+        assert_eq!(count_ways_to_beat(&race), time - 1);
+        // All holds from 0 to time beat a negative record
+        assert_eq!(count_ways_to_beat(&Race { time, distance: -1 }), time + 1);
+
+        // The optimum ties the record exactly, so the roots are close but no whole hold lies between them
+        let half: i128 = 10_i128.pow(12);
+        let race = Race {
+            time: 2 * half + 1,
+            distance: half * (half + 1),
+        };
+        assert_eq!(count_ways_to_beat(&race), 0);
+    }
+
+    #[test]
+    fn analyse_race_should_match_count_ways_for_the_puzzle_model() {
+        let model = RaceModel::LinearCharge { rate: 1 };
+        let race = Race {
+            time: 30,
+            distance: 200,
+        };
+
+        let expected = RaceAnalysis {
+            winning_holds: Some((11, 19)),
+            optimal_hold: 15,
+            optimal_distance: 225,
+            margin: 25,
+        };
+
+        assert_eq!(analyse_race(&race, &model), expected);
+        assert_eq!(count_ways_to_beat(&race), 19 - 11 + 1);
+
+        // Every hold from 0 to time beats a negative record, both count time + 1 of them
+        let race = Race {
+            time: 30,
+            distance: -1,
+        };
+        assert_eq!(analyse_race(&race, &model).winning_holds, Some((0, 30)));
+        assert_eq!(count_ways_to_beat(&race), 31);
+    }
+
+    #[test]
+    fn analyse_race_should_agree_with_scanning_for_all_models() {
+        let models = [
+            RaceModel::LinearCharge { rate: 3 },
+            RaceModel::CappedSpeed {
+                rate: 2,
+                max_speed: 9,
+            },
+            RaceModel::DiminishingAcceleration { rate: 6, decay: 1 },
+            RaceModel::DiminishingAcceleration { rate: 7, decay: 3 },
+        ];
+
+        for model in models {
+            for time in 1..40 {
+                for distance in (0..200).step_by(7) {
+                    let race = Race { time, distance };
+                    let analysis = analyse_race(&race, &model);
+
+                    let distances = (0..=time)
+                        .map(|hold| model.distance(&race, hold))
+                        .collect::<Vec<_>>();
+                    let wins = (0..=time)
+                        .filter(|hold| distances[*hold as usize] > distance)
+                        .collect::<Vec<_>>();
+
+                    assert_eq!(analysis.optimal_distance, *distances.iter().max().unwrap());
+                    assert_eq!(
+                        analysis.winning_holds,
+                        wins.first().map(|first| (*first, *wins.last().unwrap()))
+                    );
+                    if let Some((first, last)) = analysis.winning_holds {
+                        assert_eq!((last - first + 1) as usize, wins.len());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn analyse_race_should_handle_boats_standing_still() {
+        let models = [
+            RaceModel::LinearCharge { rate: 0 },
+            RaceModel::CappedSpeed {
+                rate: 3,
+                max_speed: 0,
+            },
+            RaceModel::DiminishingAcceleration { rate: 0, decay: 2 },
+        ];
+
+        for model in models {
+            for time in 0..10 {
+                let race = Race { time, distance: 0 };
+                let expected = RaceAnalysis {
+                    winning_holds: None,
+                    optimal_hold: 0,
+                    optimal_distance: 0,
+                    margin: 0,
+                };
+                assert_eq!(analyse_race(&race, &model), expected);
+
+                // Standing still beats a negative record with any hold
+                let race = Race { time, distance: -1 };
+                assert_eq!(analyse_race(&race, &model).winning_holds, Some((0, time)));
+            }
+        }
+    }
+
+    #[test]
+    fn parse_models_should_read_per_race_models() {
+        let contents = "Time: 7 15 30\nDistance: 9 40 200\nModel: capped:1:5 diminishing:4:1";
+
+        let expected = [
+            RaceModel::CappedSpeed {
+                rate: 1,
+                max_speed: 5,
+            },
+            RaceModel::DiminishingAcceleration { rate: 4, decay: 1 },
+            RaceModel::LinearCharge { rate: 1 },
+        ]
+        .to_vec();
+
+        assert_eq!(parse_input_1(contents.to_string()).len(), 3);
+        assert_eq!(parse_models(contents, 3), Some(expected));
+        assert_eq!(parse_models("Model: warp:9", 1), None);
+        assert_eq!(parse_models("Model: linear:-1", 1), None);
+        assert_eq!(parse_models("Model: diminishing:4:-1", 1), None);
+    }
+}
@@ -1,16 +1,20 @@
-use std::{cmp::Ordering, collections::HashMap, error::Error, fs};
+use std::{collections::HashMap, error::Error, fs, path::Path};
 
 type Card = i32;
 
+const TWO: Card = 2;
+const JACK: Card = 11;
+const ACE: Card = 14;
+
 fn parse_cards(cards: &str) -> Vec<Card> {
     cards
         .chars()
         .filter_map(|char| -> Option<Card> {
             match char {
-                'A' => Some(14),
+                'A' => Some(ACE),
                 'K' => Some(13),
                 'Q' => Some(12),
-                'J' => Some(11),
+                'J' => Some(JACK),
                 'T' => Some(10),
                 '9' => Some(9),
                 '8' => Some(8),
@@ -19,7 +23,7 @@ fn parse_cards(cards: &str) -> Vec<Card> {
                 '5' => Some(5),
                 '4' => Some(4),
                 '3' => Some(3),
-                '2' => Some(2),
+                '2' => Some(TWO),
                 _ => None,
             }
         })
@@ -93,108 +97,417 @@ fn hand_value(hand: &Hand) -> u8 {
     }
 }
 
-fn cmp_cards(cards1: &Vec<Card>, cards2: &Vec<Card>) -> Ordering {
-    cards1
-        .iter()
-        .zip(cards2.iter())
-        .map(|(card1, card2)| card1.cmp(card2))
-        .filter(|order| order != &Ordering::Equal)
-        .next()
-        .unwrap_or(Ordering::Equal)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum HandType {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+// How hands of the same type are ordered
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TieBreak {
+    // Compare the cards in the order they were dealt, as the puzzle does
+    InOrder,
+    // Compare the cards of bigger groups first and higher cards first within groups, as poker does
+    Grouped,
 }
 
-fn cmp_hands_by_cards(hand1: &Hand, hand2: &Hand) -> Ordering {
-    cmp_cards(&hand1.cards, &hand2.cards)
+#[derive(Clone, Debug, PartialEq)]
+struct RuleSet {
+    // Cards from weakest to strongest
+    card_order: Vec<Card>,
+    // Cards that stand in for whatever card makes the hand strongest
+    wildcards: Vec<Card>,
+    // Recognised hand types from weakest to strongest
+    hand_types: Vec<HandType>,
+    tie_break: TieBreak,
 }
 
-fn compare_hands(hand1: &Hand, hand2: &Hand) -> Ordering {
-    let hand_order = hand_value(&hand1).cmp(&hand_value(&hand2));
+impl RuleSet {
+    // The rules of the first puzzle
+    fn standard() -> RuleSet {
+        RuleSet {
+            card_order: (TWO..=ACE).collect(),
+            wildcards: Vec::new(),
+            hand_types: Vec::from([
+                HandType::HighCard,
+                HandType::OnePair,
+                HandType::TwoPair,
+                HandType::ThreeOfAKind,
+                HandType::FullHouse,
+                HandType::FourOfAKind,
+                HandType::FiveOfAKind,
+            ]),
+            tie_break: TieBreak::InOrder,
+        }
+    }
 
-    if hand_order != Ordering::Equal {
-        return hand_order;
+    // The rules of the second puzzle, where jacks are jokers
+    fn jokers() -> RuleSet {
+        RuleSet::standard().with_wildcards(&[JACK])
     }
 
-    return cmp_hands_by_cards(hand1, hand2);
+    // Moves the given cards to the weak end of the card order and makes them wild
+    fn with_wildcards(self, wildcards: &[Card]) -> RuleSet {
+        let others = self
+            .card_order
+            .into_iter()
+            .filter(|card| !wildcards.contains(card));
+
+        RuleSet {
+            card_order: wildcards.iter().copied().chain(others).collect(),
+            wildcards: wildcards.to_vec(),
+            ..self
+        }
+    }
+
+    // Recognises five consecutive cards, ranking them between three of a kind and a full house.
+    // Camel Cards have no suits, so there is no flush to recognise alongside them.
+    fn with_straights(self) -> RuleSet {
+        let mut hand_types = self.hand_types;
+        if !hand_types.contains(&HandType::Straight) {
+            let position = hand_types
+                .iter()
+                .position(|hand_type| *hand_type == HandType::FullHouse)
+                .unwrap_or(hand_types.len());
+            hand_types.insert(position, HandType::Straight);
+        }
+
+        RuleSet { hand_types, ..self }
+    }
+
+    fn from_name(name: &str) -> Option<RuleSet> {
+        match name {
+            "standard" => Some(RuleSet::standard()),
+            "jokers" => Some(RuleSet::jokers()),
+            "wild-twos" => Some(RuleSet::standard().with_wildcards(&[TWO])),
+            "straights" => Some(RuleSet::standard().with_straights()),
+            "poker" => Some(RuleSet {
+                tie_break: TieBreak::Grouped,
+                ..RuleSet::standard().with_straights()
+            }),
+            _ => None,
+        }
+    }
+
+    fn rank(&self, hand_type: HandType) -> Option<usize> {
+        self.hand_types.iter().position(|other| *other == hand_type)
+    }
+
+    fn strength(&self, card: Card) -> usize {
+        self.card_order
+            .iter()
+            .position(|other| *other == card)
+            .unwrap_or(0)
+    }
 }
 
-fn first() -> Result<(), Box<dyn Error>> {
-    let paths = ["./inputs/07/example-1.txt", "./inputs/07/input.txt"];
+// The hand type of a hand without wildcards
+fn natural_hand_type(hand: &Hand, rules: &RuleSet) -> HandType {
+    let grouped = classify(hand);
 
-    for path in paths {
-        let contents = fs::read_to_string(path)?;
-        let mut hands = parse_hands(contents);
+    // Straights follow the natural ranks, wherever a rule set moves the wildcards in its card order
+    let mut cards = hand.cards.clone();
+    cards.sort();
+    let is_straight = cards.len() == 5 && cards.windows(2).all(|pair| pair[1] == pair[0] + 1);
+
+    if is_straight && rules.rank(HandType::Straight) > rules.rank(grouped) {
+        return HandType::Straight;
+    }
+
+    grouped
+}
 
-        hands.sort_by(compare_hands);
+/*
+ * Finds the strongest hand wildcards can turn a hand into by trying all substitutions.
+ * The hand type only depends on which cards there are and not on their order,
+ * so only multisets of substitutes are tried and results are memoised per multiset of natural cards.
+ */
+struct JokerResolver<'a> {
+    rules: &'a RuleSet,
+    // Substitutes for the wildcards by the sorted natural cards and the number of wildcards
+    memo: HashMap<(Vec<Card>, usize), Vec<Card>>,
+}
+
+impl JokerResolver<'_> {
+    fn new(rules: &RuleSet) -> JokerResolver<'_> {
+        JokerResolver {
+            rules,
+            memo: HashMap::new(),
+        }
+    }
 
-        let bets = hands.iter().map(|hand| hand.bet);
-        let total_winnings = bets
-            .enumerate()
-            .map(|(rank, bet)| {
-                let rank = rank + 1;
-                bet * i32::try_from(rank).unwrap()
+    // The hand with all wildcards substituted
+    fn resolve(&mut self, hand: &Hand) -> Hand {
+        let is_wild = |card: &Card| self.rules.wildcards.contains(card);
+
+        let mut natural = hand
+            .cards
+            .iter()
+            .filter(|card| !is_wild(card))
+            .copied()
+            .collect::<Vec<_>>();
+        natural.sort();
+        let wildcard_count = hand.cards.len() - natural.len();
+
+        let key = (natural, wildcard_count);
+        let substitutes = match self.memo.get(&key) {
+            Some(substitutes) => substitutes.clone(),
+            None => {
+                let substitutes = self.best_substitutes(&key.0, wildcard_count);
+                self.memo.insert(key, substitutes.clone());
+                substitutes
+            }
+        };
+
+        let mut substitutes = substitutes.into_iter();
+        let cards = hand
+            .cards
+            .iter()
+            .map(|card| {
+                if self.rules.wildcards.contains(card) {
+                    substitutes.next().unwrap_or(*card)
+                } else {
+                    *card
+                }
             })
-            .sum::<i32>();
+            .collect();
 
-        println!("{}", total_winnings);
+        Hand {
+            cards,
+            bet: hand.bet,
+        }
     }
 
-    Ok(())
-}
+    fn best_substitutes(&self, natural: &[Card], wildcard_count: usize) -> Vec<Card> {
+        // Wildcards may also stand in for their own card, as a joker completing a straight as the jack
+        let candidates = self.rules.card_order.clone();
+
+        // Multisets of candidates, each as indices into candidates in descending order
+        let mut best: Option<(Option<usize>, Vec<Card>)> = None;
+        let mut chosen: Vec<usize> = Vec::new();
+
+        loop {
+            if chosen.len() == wildcard_count {
+                let substitutes = chosen
+                    .iter()
+                    .map(|index| candidates[*index])
+                    .collect::<Vec<_>>();
+                let hand = Hand {
+                    cards: natural.iter().chain(substitutes.iter()).copied().collect(),
+                    bet: 0,
+                };
+                let rank = self.rules.rank(natural_hand_type(&hand, self.rules));
+
+                // Candidates are visited from strongest to weakest, so ties keep the stronger substitutes
+                if best.as_ref().is_none_or(|(best_rank, _)| rank > *best_rank) {
+                    best = Some((rank, substitutes));
+                }
+            }
 
-fn fill_jokers(hand: &Hand) -> Hand {
-    let joker = 11;
-    let ace = 14;
+            // Advance to the next multiset, like counting down with non-increasing digits
+            if chosen.len() < wildcard_count && !candidates.is_empty() {
+                chosen.push(*chosen.last().unwrap_or(&(candidates.len() - 1)));
+                continue;
+            }
 
-    let card_counts = count_cards(hand);
+            while let Some(last) = chosen.pop() {
+                if last > 0 {
+                    chosen.push(last - 1);
+                    break;
+                }
+            }
 
-    let (max_card, max_card_count) = card_counts
-        .iter()
-        .filter(|(card, _)| **card != joker)
-        .max_by(|a, b| a.1.cmp(b.1))
-        .unwrap_or((&0, &0));
+            if chosen.is_empty() {
+                break;
+            }
+        }
+
+        best.map(|(_, substitutes)| substitutes).unwrap_or_default()
+    }
 
-    let chosen_card = if max_card_count > &1 { max_card } else { &ace };
+    fn hand_type(&mut self, hand: &Hand) -> HandType {
+        let resolved = self.resolve(hand);
 
-    let cards = hand
+        natural_hand_type(&resolved, self.rules)
+    }
+}
+
+// The hand type by groups of equal cards only
+fn classify(hand: &Hand) -> HandType {
+    match hand_value(hand) {
+        7 => HandType::FiveOfAKind,
+        6 => HandType::FourOfAKind,
+        5 => HandType::FullHouse,
+        4 => HandType::ThreeOfAKind,
+        3 => HandType::TwoPair,
+        2 => HandType::OnePair,
+        _ => HandType::HighCard,
+    }
+}
+
+fn tie_break_strengths(hand: &Hand, rules: &RuleSet) -> Vec<usize> {
+    let mut strengths = hand
         .cards
         .iter()
-        .map(|current| {
-            if *current == joker {
-                *chosen_card
-            } else {
-                *current
+        .map(|card| rules.strength(*card))
+        .collect::<Vec<_>>();
+
+    if rules.tie_break == TieBreak::Grouped {
+        let counts = count_cards(hand);
+        let mut cards = hand.cards.clone();
+        cards.sort_by_key(|card| (counts[card], rules.strength(*card)));
+        cards.reverse();
+
+        strengths = cards.iter().map(|card| rules.strength(*card)).collect();
+    }
+
+    strengths
+}
+
+// One line of the ranking report
+#[derive(Debug, PartialEq)]
+struct ReportRow {
+    // Position of the hand in the input
+    index: usize,
+    cards: Vec<Card>,
+    // The cards after substituting wildcards
+    filled: Vec<Card>,
+    hand_type: HandType,
+    rank: usize,
+    bet: i32,
+    winnings: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ReportColumn {
+    Input,
+    Type,
+    Rank,
+    Bid,
+    Winnings,
+}
+
+impl ReportColumn {
+    fn from_name(name: &str) -> Option<ReportColumn> {
+        match name {
+            "input" => Some(ReportColumn::Input),
+            "type" => Some(ReportColumn::Type),
+            "rank" => Some(ReportColumn::Rank),
+            "bid" => Some(ReportColumn::Bid),
+            "winnings" => Some(ReportColumn::Winnings),
+            _ => None,
+        }
+    }
+}
+
+// Ranks all hands from weakest to strongest, explaining how each one was scored
+fn ranking_report(hands: &[Hand], rules: &RuleSet) -> Vec<ReportRow> {
+    let mut resolver = JokerResolver::new(rules);
+    let mut ranked = hands.iter().enumerate().collect::<Vec<_>>();
+    ranked.sort_by_cached_key(|(_, hand)| {
+        (
+            rules.rank(resolver.hand_type(hand)),
+            tie_break_strengths(hand, rules),
+        )
+    });
+
+    ranked
+        .into_iter()
+        .enumerate()
+        .map(|(position, (index, hand))| {
+            let filled = resolver.resolve(hand);
+            let rank = position + 1;
+
+            ReportRow {
+                index,
+                cards: hand.cards.clone(),
+                hand_type: natural_hand_type(&filled, rules),
+                filled: filled.cards,
+                rank,
+                bet: hand.bet,
+                winnings: hand.bet * i32::try_from(rank).unwrap(),
             }
         })
-        .collect::<Vec<_>>();
+        .collect()
+}
 
-    Hand {
-        cards,
-        bet: hand.bet,
+// Sorts the report by the given column, ties are kept in rank order
+fn sort_report(report: &mut [ReportRow], column: ReportColumn) {
+    match column {
+        ReportColumn::Input => report.sort_by_key(|row| row.index),
+        ReportColumn::Type => report.sort_by_key(|row| row.hand_type),
+        ReportColumn::Rank => report.sort_by_key(|row| row.rank),
+        ReportColumn::Bid => report.sort_by_key(|row| row.bet),
+        ReportColumn::Winnings => report.sort_by_key(|row| row.winnings),
     }
 }
 
-fn compare_hands_with_jokers(hand1: &Hand, hand2: &Hand) -> Ordering {
-    let hand_order = hand_value(&fill_jokers(hand1)).cmp(&hand_value(&fill_jokers(hand2)));
+fn report_csv(report: &[ReportRow]) -> String {
+    let mut lines = Vec::from([String::from("index,cards,filled,type,rank,bid,winnings")]);
+
+    for row in report {
+        lines.push(format!(
+            "{},{},{},{:?},{},{},{}",
+            row.index,
+            format_cards(&row.cards),
+            format_cards(&row.filled),
+            row.hand_type,
+            row.rank,
+            row.bet,
+            row.winnings
+        ));
+    }
+
+    lines.join("\n") + "\n"
+}
 
-    if hand_order != Ordering::Equal {
-        return hand_order;
+fn report_table(report: &[ReportRow]) -> String {
+    let mut lines = Vec::from([format!(
+        "{:>5} {:>5}  {:<5}  {:<6}  {:<12} {:>5} {:>9}",
+        "index", "rank", "cards", "filled", "type", "bid", "winnings"
+    )]);
+
+    for row in report {
+        lines.push(format!(
+            "{:>5} {:>5}  {:<5}  {:<6}  {:<12} {:>5} {:>9}",
+            row.index,
+            row.rank,
+            format_cards(&row.cards),
+            format_cards(&row.filled),
+            format!("{:?}", row.hand_type),
+            row.bet,
+            row.winnings
+        ));
     }
 
-    let joker = &11;
-    let weakest = 1;
+    lines.join("\n")
+}
 
-    let joker_cards_1 = hand1
-        .cards
-        .iter()
-        .map(|card| if card == joker { weakest } else { *card })
-        .collect::<Vec<_>>();
-    let joker_cards_2 = hand2
-        .cards
+fn total_winnings(hands: Vec<Hand>, rules: &RuleSet) -> i32 {
+    ranking_report(&hands, rules)
         .iter()
-        .map(|card| if card == joker { weakest } else { *card })
-        .collect::<Vec<_>>();
+        .map(|row| row.winnings)
+        .sum()
+}
+
+fn first() -> Result<(), Box<dyn Error>> {
+    let paths = ["./inputs/07/example-1.txt", "./inputs/07/input.txt"];
+
+    for path in paths {
+        let contents = fs::read_to_string(path)?;
+        let hands = parse_hands(contents);
 
-    cmp_cards(&joker_cards_1, &joker_cards_2)
+        println!("{}", total_winnings(hands, &RuleSet::standard()));
+    }
+
+    Ok(())
 }
 
 fn second() -> Result<(), Box<dyn Error>> {
@@ -202,26 +515,91 @@ fn second() -> Result<(), Box<dyn Error>> {
 
     for path in paths {
         let contents = fs::read_to_string(path)?;
-        let mut hands = parse_hands(contents);
+        let hands = parse_hands(contents);
 
-        hands.sort_by(compare_hands_with_jokers);
+        println!("{}", total_winnings(hands, &RuleSet::jokers()));
+    }
 
-        let bets = hands.iter().map(|hand| hand.bet);
-        let total_winnings = bets
-            .enumerate()
-            .map(|(rank, bet)| {
-                let rank = rank + 1;
-                bet * i32::try_from(rank).unwrap()
-            })
-            .sum::<i32>();
+    Ok(())
+}
+
+fn format_cards(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(|card| match *card {
+            ACE => 'A',
+            13 => 'K',
+            12 => 'Q',
+            JACK => 'J',
+            10 => 'T',
+            card => char::from_digit(card as u32, 10).unwrap_or('?'),
+        })
+        .collect()
+}
+
+fn play(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let paths = ["./inputs/07/example-1.txt", "./inputs/07/input.txt"];
+    let usage = "Usage: 7 <rules|jokers> <rule set> \
+        | report <rule set> [<input|type|rank|bid|winnings> [csv <output directory>]] \
+        (rule sets: standard, jokers, wild-twos, straights, poker)";
+
+    let [command, name, options @ ..] = args else {
+        return Err(usage.into());
+    };
+    let rules = RuleSet::from_name(name).ok_or(usage)?;
+
+    for path in paths {
+        let contents = fs::read_to_string(path)?;
+        let hands = parse_hands(contents);
 
-        println!("{}", total_winnings);
+        match (command.as_str(), options) {
+            ("rules", []) => println!("{}", total_winnings(hands, &rules)),
+            ("jokers", []) => {
+                let mut resolver = JokerResolver::new(&rules);
+
+                for hand in hands.iter() {
+                    let filled = resolver.resolve(hand);
+
+                    println!(
+                        "{} -> {} ({:?})",
+                        format_cards(&hand.cards),
+                        format_cards(&filled.cards),
+                        resolver.hand_type(hand)
+                    );
+                }
+            }
+            ("report", []) => println!("{}", report_table(&ranking_report(&hands, &rules))),
+            ("report", [column, output @ ..]) => {
+                let column = ReportColumn::from_name(column).ok_or(usage)?;
+                let mut report = ranking_report(&hands, &rules);
+                sort_report(&mut report, column);
+
+                match output {
+                    [] => println!("{}", report_table(&report)),
+                    [format, directory] if format == "csv" => {
+                        let file_name = Path::new(path).with_extension("csv");
+                        let file_name = file_name.file_name().ok_or(usage)?;
+                        let output = Path::new(directory).join(file_name);
+
+                        fs::write(&output, report_csv(&report))?;
+                        println!("Wrote {}", output.display());
+                    }
+                    _ => return Err(usage.into()),
+                }
+            }
+            _ => return Err(usage.into()),
+        }
     }
 
     Ok(())
 }
 
 pub fn main() -> Result<(), Box<dyn Error>> {
+    let args = crate::task_args();
+    if !args.is_empty() {
+        return play(&args);
+    }
+
     println!("07-1:");
     first()?;
     println!("07-2:");
@@ -232,7 +610,10 @@ pub fn main() -> Result<(), Box<dyn Error>> {
 
 #[cfg(test)]
 mod tests {
-    use crate::task_07::{fill_jokers, hand_value, parse_hands, Hand};
+    use crate::task_07::{
+        hand_value, parse_hands, ranking_report, report_csv, sort_report, total_winnings, Hand,
+        HandType, JokerResolver, ReportColumn, RuleSet, TWO,
+    };
 
     #[test]
     fn hand_value_should_behave() {
@@ -306,9 +687,11 @@ mod tests {
         .join("\n");
 
         let hands = parse_hands(contents);
+        let rules = RuleSet::jokers();
+        let mut resolver = JokerResolver::new(&rules);
         let filled_hands = hands
             .iter()
-            .map(|hand| fill_jokers(hand))
+            .map(|hand| resolver.resolve(hand))
             .collect::<Vec<_>>();
 
         let contents = [
@@ -333,8 +716,118 @@ mod tests {
         };
 
         let expected = [2, 14, 14, 2, 14].to_vec();
-        let actual = fill_jokers(&input).cards;
+        let actual = JokerResolver::new(&RuleSet::jokers()).resolve(&input).cards;
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn total_winnings_should_match_both_puzzles() {
+        let contents = [
+            "32T3K 765",
+            "T55J5 684",
+            "KK677 28",
+            "KTJJT 220",
+            "QQQJA 483",
+        ]
+        .join("\n");
+
+        assert_eq!(
+            total_winnings(parse_hands(contents.clone()), &RuleSet::standard()),
+            6440
+        );
+        assert_eq!(
+            total_winnings(parse_hands(contents), &RuleSet::jokers()),
+            5905
+        );
+    }
+
+    #[test]
+    fn ranking_report_should_explain_every_hand() {
+        let contents = [
+            "32T3K 765",
+            "T55J5 684",
+            "KK677 28",
+            "KTJJT 220",
+            "QQQJA 483",
+        ]
+        .join("\n");
+
+        let mut report = ranking_report(&parse_hands(contents), &RuleSet::jokers());
+
+        let order = report.iter().map(|row| row.index).collect::<Vec<_>>();
+        assert_eq!(order, [0, 2, 1, 4, 3].to_vec());
+        assert_eq!(report[2].filled, [10, 5, 5, 5, 5].to_vec());
+        assert_eq!(report[2].hand_type, HandType::FourOfAKind);
+        assert_eq!(report[2].winnings, 684 * 3);
+        assert_eq!(report.iter().map(|row| row.winnings).sum::<i32>(), 5905);
+
+        sort_report(&mut report, ReportColumn::Input);
+
+        let csv = report_csv(&report);
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "index,cards,filled,type,rank,bid,winnings");
+        assert_eq!(lines[1], "0,32T3K,32T3K,OnePair,1,765,765");
+        assert_eq!(lines[4], "3,KTJJT,KTTTT,FourOfAKind,5,220,1100");
+    }
+
+    #[test]
+    fn rule_sets_should_support_extra_variants() {
+        let hands = parse_hands(["23456 1", "2345J 1", "T2T9T 1", "22JKQ 1", "89TQK 1"].join("\n"));
+        let straights = RuleSet::from_name("straights").unwrap();
+        let jokers_and_straights = RuleSet::jokers().with_straights();
+        let wild_twos = RuleSet::from_name("wild-twos").unwrap();
+        let standard = RuleSet::standard();
+        let mut straights = JokerResolver::new(&straights);
+        let mut jokers_and_straights = JokerResolver::new(&jokers_and_straights);
+        let mut wild_twos_resolver = JokerResolver::new(&wild_twos);
+
+        assert_eq!(straights.hand_type(&hands[0]), HandType::Straight);
+        assert_eq!(straights.hand_type(&hands[1]), HandType::HighCard);
+        assert_eq!(
+            jokers_and_straights.hand_type(&hands[1]),
+            HandType::Straight
+        );
+        assert_eq!(
+            JokerResolver::new(&standard).hand_type(&hands[0]),
+            HandType::HighCard
+        );
+        assert_eq!(
+            wild_twos_resolver.hand_type(&hands[2]),
+            HandType::FourOfAKind
+        );
+        // Only the twos are wild, the jack stays a natural card
+        assert_eq!(
+            wild_twos_resolver.hand_type(&hands[3]),
+            HandType::ThreeOfAKind
+        );
+        assert_eq!(wild_twos.card_order[..2], [TWO, 3]);
+
+        // Moving the jack out of the card order does not close the gap between ten and queen
+        assert_eq!(straights.hand_type(&hands[4]), HandType::HighCard);
+        assert_eq!(
+            jokers_and_straights.hand_type(&hands[4]),
+            HandType::HighCard
+        );
+    }
+
+    #[test]
+    fn joker_resolver_should_find_the_strongest_substitution() {
+        let poker = RuleSet::from_name("poker").unwrap().with_wildcards(&[11]);
+        let mut resolver = JokerResolver::new(&poker);
+
+        let hands = parse_hands(["9TJQ8 1", "JJ9T8 2", "TJQJ9 3", "JJJJJ 4"].join("\n"));
+        let resolved = hands
+            .iter()
+            .map(|hand| resolver.resolve(hand).cards)
+            .collect::<Vec<_>>();
+
+        // Filling with the most frequent card would only ever make a pair here
+        assert_eq!(resolver.hand_type(&hands[0]), HandType::Straight);
+        // The wild jack stands in for the jack the straight is missing
+        assert_eq!(resolved[0], [9, 10, 11, 12, 8].to_vec());
+        assert_eq!(resolver.hand_type(&hands[1]), HandType::Straight);
+        assert_eq!(resolver.hand_type(&hands[2]), HandType::Straight);
+        assert_eq!(resolved[3], [14, 14, 14, 14, 14].to_vec());
+    }
 }
@@ -1,14 +1,39 @@
 use regex::Regex;
-use std::{collections::HashMap, error::Error, fs};
+use std::{collections::HashMap, error::Error, fmt, fs};
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 enum Step {
-    Left,
-    Right,
+    Left = 0,
+    Right = 1,
 }
 
 type Path = Vec<Step>;
-type Graph = HashMap<String, (String, String)>;
+type NodeId = usize;
+
+/*
+ * Node names are interned into ids in order of first appearance.
+ * The successors of a node are stored next to each other, indexed by node id and step.
+ */
+#[derive(Debug)]
+struct Graph {
+    names: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    successors: Vec<NodeId>,
+}
+
+impl Graph {
+    fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    fn name(&self, node: NodeId) -> &str {
+        &self.names[node]
+    }
+
+    fn nodes(&self) -> impl Iterator<Item = NodeId> {
+        0..self.names.len()
+    }
+}
 
 #[derive(Debug)]
 struct Input {
@@ -26,21 +51,44 @@ fn parse_path(line: String) -> Path {
         .collect()
 }
 
-fn parse_graph(graph: String) -> Graph {
+// Returns None if a node leads to a node that is not defined
+fn parse_graph(graph: String) -> Option<Graph> {
     let line_regex = Regex::new(r"(?<from>\w+) = \((?<left>\w+), (?<right>\w+)\)").unwrap();
 
-    graph
+    let lines = graph
         .lines()
-        .filter_map(|line| -> Option<(String, (String, String))> {
+        .filter_map(|line| -> Option<(&str, &str, &str)> {
             let captures = line_regex.captures(line)?;
 
             let from = captures.name("from")?.as_str();
             let left = captures.name("left")?.as_str();
             let right = captures.name("right")?.as_str();
 
-            Some((from.to_string(), (left.to_string(), right.to_string())))
+            Some((from, left, right))
         })
-        .collect()
+        .collect::<Vec<_>>();
+
+    let names = lines
+        .iter()
+        .map(|(from, _, _)| from.to_string())
+        .collect::<Vec<_>>();
+    let ids = names
+        .iter()
+        .enumerate()
+        .map(|(id, name)| (name.clone(), id))
+        .collect::<HashMap<_, _>>();
+
+    let mut successors = Vec::with_capacity(2 * names.len());
+    for (_, left, right) in lines {
+        successors.push(*ids.get(left)?);
+        successors.push(*ids.get(right)?);
+    }
+
+    Some(Graph {
+        names,
+        ids,
+        successors,
+    })
 }
 
 fn parse_input(contents: String) -> Option<Input> {
@@ -51,22 +99,18 @@ fn parse_input(contents: String) -> Option<Input> {
 
     Some(Input {
         path: parse_path(path.to_string()),
-        graph: parse_graph(graph.to_string()),
+        graph: parse_graph(graph.to_string())?,
     })
 }
 
-fn apply_step(graph: &Graph, node: &String, step: &Step) -> String {
-    let (left, right) = graph.get(node).unwrap();
-
-    match step {
-        Step::Left => left.to_string(),
-        Step::Right => right.to_string(),
-    }
+fn apply_step(graph: &Graph, node: NodeId, step: Step) -> NodeId {
+    graph.successors[2 * node + step as usize]
 }
 
-fn travel(input: Input) -> i32 {
-    let start = "AAA".to_string();
-    let end = "ZZZ".to_string();
+// Returns None if either end of the journey is missing
+fn travel(input: Input) -> Option<i64> {
+    let start = input.graph.id("AAA")?;
+    let end = input.graph.id("ZZZ")?;
 
     let mut current = start;
     let mut steps = 0;
@@ -76,11 +120,11 @@ fn travel(input: Input) -> i32 {
             break;
         }
 
-        current = apply_step(&input.graph, &current, step);
+        current = apply_step(&input.graph, current, *step);
         steps += 1;
     }
 
-    return steps;
+    Some(steps)
 }
 
 fn first() -> Result<(), Box<dyn Error>> {
@@ -94,81 +138,190 @@ fn first() -> Result<(), Box<dyn Error>> {
         let contents = fs::read_to_string(path)?;
         let input = parse_input(contents).unwrap();
 
-        let steps = travel(input);
+        let steps = travel(input).ok_or("AAA or ZZZ is missing")?;
         println!("Total setps: {}", steps);
     }
 
     Ok(())
 }
 
-fn start_nodes(input: &Input) -> Vec<String> {
+fn start_nodes(input: &Input) -> Vec<NodeId> {
     let start_node = Regex::new(r"\w\w[aA]$").unwrap();
 
-    input
+    let mut starts: Vec<NodeId> = input
         .graph
-        .keys()
-        .filter(|key| start_node.is_match(&key))
-        .map(|x| x.to_string())
-        .collect()
+        .nodes()
+        .filter(|node| start_node.is_match(input.graph.name(*node)))
+        .collect();
+    starts.sort_by_key(|node| input.graph.name(*node));
+
+    starts
 }
 
 fn is_finish_node(node: &str) -> bool {
     node.ends_with("Z")
 }
 
-fn ghost_travel(input: &Input, start: &String) -> i32 {
-    let mut current = start.to_owned();
-    let mut steps = 0;
+#[derive(Debug, PartialEq)]
+enum GhostError {
+    EmptyPath,
+    NoStartNodes,
+    // The ghosts never stand on finish nodes at the same time
+    NoSolution,
+}
 
-    for step in input.path.iter().cycle() {
-        if is_finish_node(&current) {
-            break;
+impl fmt::Display for GhostError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GhostError::EmptyPath => write!(f, "the path has no steps"),
+            GhostError::NoStartNodes => write!(f, "there are no start nodes"),
+            GhostError::NoSolution => {
+                write!(f, "the ghosts never reach finish nodes at the same time")
+            }
         }
-
-        current = apply_step(&input.graph, &current, step);
-        steps += 1;
     }
+}
 
-    steps
+impl Error for GhostError {}
+
+/*
+ * A ghost is in a state given by its node and the index of its next instruction.
+ * As there are finitely many states the walk runs through a transient part into a cycle.
+ */
+#[derive(Debug, PartialEq)]
+struct GhostCycle {
+    // Steps taken before entering the cycle
+    transient: i128,
+    cycle_length: i128,
+    // Steps before entering the cycle that end on a finish node
+    transient_hits: Vec<i128>,
+    // Steps during the first pass of the cycle that end on a finish node
+    cycle_hits: Vec<i128>,
+}
+
+impl GhostCycle {
+    fn is_hit(&self, steps: i128) -> bool {
+        if steps < self.transient {
+            self.transient_hits.contains(&steps)
+        } else {
+            let offset = (steps - self.transient) % self.cycle_length;
+            self.cycle_hits.contains(&(self.transient + offset))
+        }
+    }
 }
 
-// credit to https://gist.github.com/victor-iyi/8a84185c1d52419b0d4915a648d5e3e1
-fn gcd(mut n: i128, mut m: i128) -> i128 {
-    assert!(n > 0 && m > 0);
+fn analyse_cycle(input: &Input, start: NodeId) -> Result<GhostCycle, GhostError> {
+    if input.path.is_empty() {
+        return Err(GhostError::EmptyPath);
+    }
+
+    let finish_nodes = input
+        .graph
+        .nodes()
+        .map(|node| is_finish_node(input.graph.name(node)))
+        .collect::<Vec<_>>();
+
+    // Step count at which each state was first seen, indexed by node and instruction index
+    let mut seen: Vec<Option<i128>> = vec![None; input.graph.names.len() * input.path.len()];
+    let mut hits = Vec::new();
+    let mut current = start;
 
-    while m != 0 {
-        if m < n {
-            std::mem::swap(&mut n, &mut m);
+    for (steps, (index, step)) in (0..).zip(input.path.iter().enumerate().cycle()) {
+        let state = current * input.path.len() + index;
+        if let Some(first_seen) = seen[state] {
+            let (transient_hits, cycle_hits) = hits.into_iter().partition(|hit| *hit < first_seen);
+
+            return Ok(GhostCycle {
+                transient: first_seen,
+                cycle_length: steps - first_seen,
+                transient_hits,
+                cycle_hits,
+            });
         }
+        seen[state] = Some(steps);
 
-        m %= n;
+        if finish_nodes[current] {
+            hits.push(steps);
+        }
+
+        current = apply_step(&input.graph, current, *step);
     }
 
-    n
+    unreachable!("the path is cycled endlessly")
+}
+
+// Returns (gcd(a, b), x, y) such that a * x + b * y = gcd(a, b)
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (gcd, x, y) = extended_gcd(b, a % b);
+        (gcd, y, x - (a / b) * y)
+    }
 }
 
-// recalling https://github.com/runjak/aoc-2019/blob/master/src/12.ts
-fn lcm(values: &Vec<i128>) -> i128 {
-    assert!(values.len() > 0);
+// Chinese remainder theorem for two congruences whose moduli need not be coprime
+fn combine_congruences((a, m): (i128, i128), (b, n): (i128, i128)) -> Option<(i128, i128)> {
+    let (gcd, x, _) = extended_gcd(m, n);
+    if (b - a) % gcd != 0 {
+        return None;
+    }
 
-    let mut values = values.iter();
-    let first = values.next().unwrap();
+    let reduced = n / gcd;
+    let k = ((b - a) / gcd).rem_euclid(reduced) * x.rem_euclid(reduced) % reduced;
+    let modulus = m / gcd * n;
 
-    values.fold(*first, |acc, v| {
-        let v = *v;
-        acc * v / gcd(acc, v)
-    })
+    Some(((a + m * k).rem_euclid(modulus), modulus))
 }
 
-fn ghost_travels(input: Input) -> i128 {
-    let starts = start_nodes(&input);
+// The first step count at which all ghosts stand on finish nodes
+fn simultaneous_arrival(cycles: &[GhostCycle]) -> Result<i128, GhostError> {
+    let latest = cycles
+        .iter()
+        .max_by_key(|cycle| cycle.transient)
+        .ok_or(GhostError::NoStartNodes)?;
 
-    let cycle_lengths: Vec<i128> = starts
+    // Before the last ghost enters its cycle only its transient hits are candidates
+    let early = latest
+        .transient_hits
         .iter()
-        .map(|start| ghost_travel(&input, start) as i128)
-        .collect();
+        .find(|steps| cycles.iter().all(|cycle| cycle.is_hit(**steps)));
+    if let Some(steps) = early {
+        return Ok(*steps);
+    }
+
+    // Afterwards every ghost is within its cycle, so each hit is a congruence
+    let mut congruences = Vec::from([(0, 1)]);
+    for cycle in cycles {
+        congruences = congruences
+            .iter()
+            .flat_map(|congruence| {
+                cycle
+                    .cycle_hits
+                    .iter()
+                    .filter_map(|hit| combine_congruences(*congruence, (*hit, cycle.cycle_length)))
+            })
+            .collect();
+        congruences.sort();
+        congruences.dedup();
+    }
+
+    congruences
+        .iter()
+        .map(|(residue, modulus)| {
+            latest.transient + (residue - latest.transient).rem_euclid(*modulus)
+        })
+        .min()
+        .ok_or(GhostError::NoSolution)
+}
+
+fn ghost_travels(input: Input) -> Result<i128, GhostError> {
+    let cycles = start_nodes(&input)
+        .iter()
+        .map(|start| analyse_cycle(&input, *start))
+        .collect::<Result<Vec<_>, _>>()?;
 
-    lcm(&cycle_lengths)
+    simultaneous_arrival(&cycles)
 }
 
 fn second() -> Result<(), Box<dyn Error>> {
@@ -183,14 +336,328 @@ fn second() -> Result<(), Box<dyn Error>> {
         let contents = fs::read_to_string(path)?;
         let input = parse_input(contents).unwrap();
 
-        let steps = ghost_travels(input);
+        let steps = ghost_travels(input)?;
         println!("Total setps: {}", steps);
     }
 
     Ok(())
 }
 
+fn successors(graph: &Graph, node: NodeId) -> [NodeId; 2] {
+    [
+        apply_step(graph, node, Step::Left),
+        apply_step(graph, node, Step::Right),
+    ]
+}
+
+fn to_dot(graph: &Graph) -> String {
+    let mut lines = Vec::from(["digraph network {".to_string()]);
+
+    for node in graph.nodes() {
+        let name = graph.name(node);
+        let shape = if is_finish_node(name) {
+            "doublecircle"
+        } else if name.ends_with('A') {
+            "box"
+        } else {
+            "ellipse"
+        };
+
+        lines.push(format!("  \"{}\" [shape={}];", name, shape));
+    }
+
+    for node in graph.nodes() {
+        let [left, right] = successors(graph, node);
+
+        if left == right {
+            lines.push(format!(
+                "  \"{}\" -> \"{}\" [label=\"LR\"];",
+                graph.name(node),
+                graph.name(left)
+            ));
+        } else {
+            lines.push(format!(
+                "  \"{}\" -> \"{}\" [label=\"L\"];",
+                graph.name(node),
+                graph.name(left)
+            ));
+            lines.push(format!(
+                "  \"{}\" -> \"{}\" [label=\"R\"];",
+                graph.name(node),
+                graph.name(right)
+            ));
+        }
+    }
+
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+// Kosaraju's algorithm, the components come out with sources first
+fn strongly_connected_components(graph: &Graph) -> Vec<Vec<NodeId>> {
+    let node_count = graph.names.len();
+
+    // Order nodes by the time their depth first search finishes
+    let mut visited = vec![false; node_count];
+    let mut finished = Vec::with_capacity(node_count);
+    for root in graph.nodes() {
+        if visited[root] {
+            continue;
+        }
+
+        visited[root] = true;
+        let mut stack = Vec::from([(root, 0)]);
+        while let Some((node, next)) = stack.pop() {
+            if next == 2 {
+                finished.push(node);
+                continue;
+            }
+
+            stack.push((node, next + 1));
+            let successor = successors(graph, node)[next];
+            if !visited[successor] {
+                visited[successor] = true;
+                stack.push((successor, 0));
+            }
+        }
+    }
+
+    let mut predecessors = vec![Vec::new(); node_count];
+    for node in graph.nodes() {
+        for successor in successors(graph, node) {
+            predecessors[successor].push(node);
+        }
+    }
+
+    // Nodes reaching a root on the reversed graph that are not taken yet form its component
+    let mut assigned = vec![false; node_count];
+    let mut components = Vec::new();
+    for root in finished.into_iter().rev() {
+        if assigned[root] {
+            continue;
+        }
+
+        assigned[root] = true;
+        let mut component = Vec::new();
+        let mut stack = Vec::from([root]);
+        while let Some(node) = stack.pop() {
+            component.push(node);
+
+            for predecessor in predecessors[node].iter() {
+                if !assigned[*predecessor] {
+                    assigned[*predecessor] = true;
+                    stack.push(*predecessor);
+                }
+            }
+        }
+
+        component.sort();
+        components.push(component);
+    }
+
+    components
+}
+
+fn reachable_nodes(graph: &Graph, start: NodeId) -> Vec<bool> {
+    let mut reachable = vec![false; graph.names.len()];
+    reachable[start] = true;
+
+    let mut stack = Vec::from([start]);
+    while let Some(node) = stack.pop() {
+        for successor in successors(graph, node) {
+            if !reachable[successor] {
+                reachable[successor] = true;
+                stack.push(successor);
+            }
+        }
+    }
+
+    reachable
+}
+
+#[derive(Debug, PartialEq)]
+struct NetworkAnalysis {
+    components: Vec<Vec<NodeId>>,
+    // Indexes into components for each start node
+    reachable_components: Vec<(NodeId, Vec<usize>)>,
+    // Finish nodes that no start node can reach
+    unreachable_finishes: Vec<NodeId>,
+    // Indexes into components that no step ever leaves
+    traps: Vec<usize>,
+}
+
+fn analyse_network(input: &Input) -> NetworkAnalysis {
+    let graph = &input.graph;
+    let components = strongly_connected_components(graph);
+
+    let mut component_of = vec![0; graph.names.len()];
+    for (index, component) in components.iter().enumerate() {
+        for node in component {
+            component_of[*node] = index;
+        }
+    }
+
+    let mut reachable_from_any = vec![false; graph.names.len()];
+    let reachable_components = start_nodes(input)
+        .into_iter()
+        .map(|start| {
+            let reachable = reachable_nodes(graph, start);
+            let mut reached = graph
+                .nodes()
+                .filter(|node| reachable[*node])
+                .map(|node| {
+                    reachable_from_any[node] = true;
+                    component_of[node]
+                })
+                .collect::<Vec<_>>();
+            reached.sort();
+            reached.dedup();
+
+            (start, reached)
+        })
+        .collect();
+
+    let unreachable_finishes = graph
+        .nodes()
+        .filter(|node| is_finish_node(graph.name(*node)) && !reachable_from_any[*node])
+        .collect();
+    let traps = (0..components.len())
+        .filter(|index| {
+            components[*index].iter().all(|node| {
+                successors(graph, *node)
+                    .iter()
+                    .all(|successor| component_of[*successor] == *index)
+            })
+        })
+        .collect();
+
+    NetworkAnalysis {
+        components,
+        reachable_components,
+        unreachable_finishes,
+        traps,
+    }
+}
+
+fn format_nodes(graph: &Graph, nodes: &[NodeId]) -> String {
+    if nodes.is_empty() {
+        return "none".to_string();
+    }
+
+    nodes
+        .iter()
+        .map(|node| graph.name(*node))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn network(command: &str) -> Result<(), Box<dyn Error>> {
+    let paths = [
+        "./inputs/08/example-1.txt",
+        "./inputs/08/example-2.txt",
+        "./inputs/08/example-3.txt",
+        "./inputs/08/input.txt",
+    ];
+
+    for path in paths {
+        let contents = fs::read_to_string(path)?;
+        let input = parse_input(contents).ok_or("Could not parse input")?;
+
+        match command {
+            "cycles" => {
+                println!("{}:", path);
+                for start in start_nodes(&input) {
+                    let cycle = analyse_cycle(&input, start)?;
+
+                    println!(
+                        "{}: transient {}, cycle length {}, hits {:?} then {:?} repeating",
+                        input.graph.name(start),
+                        cycle.transient,
+                        cycle.cycle_length,
+                        cycle.transient_hits,
+                        cycle.cycle_hits
+                    );
+                }
+            }
+            "dot" => println!("{}", to_dot(&input.graph)),
+            _ => {
+                let graph = &input.graph;
+                let analysis = analyse_network(&input);
+
+                println!("{}:", path);
+                println!(
+                    "{} nodes in {} strongly connected components",
+                    graph.names.len(),
+                    analysis.components.len()
+                );
+
+                for (start, reached) in analysis.reachable_components.iter() {
+                    let sizes = reached
+                        .iter()
+                        .map(|index| analysis.components[*index].len().to_string())
+                        .collect::<Vec<_>>();
+                    let finishes = reached
+                        .iter()
+                        .flat_map(|index| analysis.components[*index].iter().copied())
+                        .filter(|node| is_finish_node(graph.name(*node)))
+                        .collect::<Vec<_>>();
+
+                    println!(
+                        "{}: reaches {} components of sizes {}, finish nodes {}",
+                        graph.name(*start),
+                        reached.len(),
+                        sizes.join(", "),
+                        format_nodes(graph, &finishes)
+                    );
+                }
+
+                println!(
+                    "Unreachable finish nodes: {}",
+                    format_nodes(graph, &analysis.unreachable_finishes)
+                );
+                let traps = analysis
+                    .traps
+                    .iter()
+                    .map(|index| {
+                        let component = &analysis.components[*index];
+                        let finishes = component
+                            .iter()
+                            .copied()
+                            .filter(|node| is_finish_node(graph.name(*node)))
+                            .collect::<Vec<_>>();
+
+                        format!(
+                            "{} nodes with finish nodes {}",
+                            component.len(),
+                            format_nodes(graph, &finishes)
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                println!(
+                    "Components that can never be left: {}",
+                    if traps.is_empty() {
+                        "none".to_string()
+                    } else {
+                        traps.join("; ")
+                    }
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
 pub fn main() -> Result<(), Box<dyn Error>> {
+    let args = crate::task_args();
+    match args.as_slice() {
+        [] => {}
+        [command] if ["cycles", "dot", "analyse"].contains(&command.as_str()) => {
+            return network(command)
+        }
+        _ => return Err("Usage: 8 cycles | dot | analyse".into()),
+    }
+
     println!("08-1:");
     first()?;
     println!("08-2:");
@@ -203,7 +670,10 @@ pub fn main() -> Result<(), Box<dyn Error>> {
 mod tests {
     use std::{error::Error, fs};
 
-    use crate::task_08::ghost_travels;
+    use crate::task_08::{
+        analyse_cycle, analyse_network, apply_step, combine_congruences, ghost_travels, to_dot,
+        travel, GhostCycle, GhostError, Step,
+    };
 
     use super::parse_input;
 
@@ -212,7 +682,219 @@ mod tests {
         let contents = fs::read_to_string("./inputs/08/example-3.txt")?;
         let input = parse_input(contents).unwrap();
 
-        assert_eq!(ghost_travels(input), 6);
+        assert_eq!(ghost_travels(input), Ok(6));
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_input_should_intern_nodes() -> Result<(), Box<dyn Error>> {
+        let contents = fs::read_to_string("./inputs/08/example-2.txt")?;
+        let input = parse_input(contents).unwrap();
+
+        let start = input.graph.id("AAA").unwrap();
+        let next = apply_step(&input.graph, start, Step::Left);
+
+        assert_eq!(input.graph.name(next), "BBB");
+        assert_eq!(travel(input), Some(6));
+
+        let missing = ["L", "", "AAA = (BBB, ZZZ)", "ZZZ = (ZZZ, ZZZ)"].join("\n");
+        assert!(parse_input(missing).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn analyse_cycle_should_find_transient_and_cycle() -> Result<(), Box<dyn Error>> {
+        let contents = fs::read_to_string("./inputs/08/example-3.txt")?;
+        let input = parse_input(contents).unwrap();
+
+        let expected = GhostCycle {
+            transient: 1,
+            cycle_length: 6,
+            transient_hits: Vec::new(),
+            cycle_hits: [3, 6].to_vec(),
+        };
+
+        let start = input.graph.id("22A").unwrap();
+        assert_eq!(analyse_cycle(&input, start)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn ghost_travels_should_handle_misaligned_cycles() {
+        // 11A reaches 11Z after 2 steps and again every 3 steps, 22A after 2 steps and then every 2 steps
+        let contents = [
+            "L",
+            "",
+            "11A = (11B, XXX)",
+            "11B = (11Z, XXX)",
+            "11Z = (11C, XXX)",
+            "11C = (11D, XXX)",
+            "11D = (11Z, XXX)",
+            "22A = (22B, XXX)",
+            "22B = (22Z, XXX)",
+            "22Z = (22C, XXX)",
+            "22C = (22Z, XXX)",
+            "XXX = (XXX, XXX)",
+        ]
+        .join("\n");
+
+        assert_eq!(ghost_travels(parse_input(contents).unwrap()), Ok(2));
+
+        let contents = [
+            "L",
+            "",
+            "11A = (11B, XXX)",
+            "11B = (11C, XXX)",
+            "11C = (11Z, XXX)",
+            "11Z = (11D, XXX)",
+            "11D = (11E, XXX)",
+            "11E = (11Z, XXX)",
+            "22A = (22Z, XXX)",
+            "22Z = (22B, XXX)",
+            "22B = (22C, XXX)",
+            "22C = (22D, XXX)",
+            "22D = (22Z, XXX)",
+            "XXX = (XXX, XXX)",
+        ]
+        .join("\n");
+
+        // Hits at 3, 6, 9, ... and 1, 5, 9, ... while the first arrivals 3 and 1 have an LCM of 3
+        assert_eq!(ghost_travels(parse_input(contents).unwrap()), Ok(9));
+    }
+
+    #[test]
+    fn ghost_travels_should_report_impossible_arrivals() {
+        let contents = [
+            "L",
+            "",
+            "11A = (11Z, XXX)",
+            "11Z = (11B, XXX)",
+            "11B = (11Z, XXX)",
+            "22A = (22B, XXX)",
+            "22B = (22Z, XXX)",
+            "22Z = (22C, XXX)",
+            "22C = (22Z, XXX)",
+            "XXX = (XXX, XXX)",
+        ]
+        .join("\n");
+
+        assert_eq!(
+            ghost_travels(parse_input(contents).unwrap()),
+            Err(GhostError::NoSolution)
+        );
+    }
+
+    #[test]
+    fn combine_congruences_should_handle_common_factors() {
+        assert_eq!(combine_congruences((2, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(combine_congruences((1, 4), (3, 6)), Some((9, 12)));
+        assert_eq!(combine_congruences((0, 4), (1, 6)), None);
+    }
+
+    #[test]
+    fn analyse_network_should_find_components_and_traps() {
+        let contents = [
+            "L",
+            "",
+            "11A = (11B, 11B)",
+            "11B = (11C, 11A)",
+            "11C = (11B, XXX)",
+            "22Z = (11A, 22Z)",
+            "XXX = (XXX, XXX)",
+        ]
+        .join("\n");
+        let input = parse_input(contents).unwrap();
+        let analysis = analyse_network(&input);
+
+        let names = |nodes: &[usize]| {
+            nodes
+                .iter()
+                .map(|node| input.graph.name(*node))
+                .collect::<Vec<_>>()
+        };
+
+        let components = analysis
+            .components
+            .iter()
+            .map(|component| names(component))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            components,
+            [
+                ["22Z"].to_vec(),
+                ["11A", "11B", "11C"].to_vec(),
+                ["XXX"].to_vec()
+            ]
+            .to_vec()
+        );
+
+        let [(start, reached)] = analysis.reachable_components.as_slice() else {
+            panic!("expected a single start node");
+        };
+        assert_eq!(input.graph.name(*start), "11A");
+        assert_eq!(reached, &[1, 2].to_vec());
+
+        assert_eq!(names(&analysis.unreachable_finishes), ["22Z"].to_vec());
+        assert_eq!(analysis.traps, [2].to_vec());
+    }
+
+    #[test]
+    fn analyse_network_should_find_traps_spanning_several_nodes() {
+        let contents = [
+            "L",
+            "",
+            "AAA = (BBB, ZZZ)",
+            "BBB = (CCC, CCC)",
+            "CCC = (BBB, BBB)",
+            "ZZZ = (AAA, ZZZ)",
+        ]
+        .join("\n");
+        let input = parse_input(contents).unwrap();
+        let analysis = analyse_network(&input);
+
+        let [trap] = analysis.traps.as_slice() else {
+            panic!("expected a single trap");
+        };
+        let trapped = analysis.components[*trap]
+            .iter()
+            .map(|node| input.graph.name(*node))
+            .collect::<Vec<_>>();
+        assert_eq!(trapped, ["BBB", "CCC"].to_vec());
+    }
+
+    #[test]
+    fn to_dot_should_label_steps() {
+        let contents = [
+            "L",
+            "",
+            "AAA = (BBB, ZZZ)",
+            "BBB = (ZZZ, ZZZ)",
+            "ZZZ = (ZZZ, ZZZ)",
+        ]
+        .join("\n");
+        let dot = to_dot(&parse_input(contents).unwrap().graph);
+
+        assert!(dot.starts_with("digraph network {"));
+        assert!(dot.contains("  \"AAA\" [shape=box];"));
+        assert!(dot.contains("  \"ZZZ\" [shape=doublecircle];"));
+        assert!(dot.contains("  \"AAA\" -> \"BBB\" [label=\"L\"];"));
+        assert!(dot.contains("  \"AAA\" -> \"ZZZ\" [label=\"R\"];"));
+        assert!(dot.contains("  \"BBB\" -> \"ZZZ\" [label=\"LR\"];"));
+    }
+
+    #[test]
+    fn to_dot_should_quote_ids_starting_with_digits() -> Result<(), Box<dyn Error>> {
+        let contents = fs::read_to_string("./inputs/08/example-3.txt")?;
+        let dot = to_dot(&parse_input(contents).unwrap().graph);
+
+        assert!(dot.contains("  \"11A\" [shape=box];"));
+        assert!(dot.contains("  \"11Z\" [shape=doublecircle];"));
+        assert!(dot.contains("  \"11A\" -> \"11B\" [label=\"L\"];"));
+        assert!(dot.contains("  \"22B\" -> \"22C\" [label=\"LR\"];"));
+        assert!(!dot.contains("  11A"));
 
         Ok(())
     }
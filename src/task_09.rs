@@ -1,6 +1,43 @@
-use std::{error::Error, fs};
+use std::{error::Error, fmt, fs};
 
-type Z = i32;
+type Z = i64;
+
+// Extrapolated values are collected, so the number of steps at once is bounded
+const MAX_STEPS: u64 = 1_000_000;
+
+#[derive(Debug, PartialEq)]
+enum ExtrapolationError {
+    Overflow,
+    // The differences never became all zero, so the sequence does not determine a polynomial
+    NotPolynomial { length: usize },
+    TooManySteps { steps: Z },
+}
+
+impl fmt::Display for ExtrapolationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExtrapolationError::Overflow => {
+                write!(f, "the values do not fit into {} bits", Z::BITS)
+            }
+            ExtrapolationError::NotPolynomial { length } => {
+                write!(
+                    f,
+                    "the {} values are not polynomial within their length",
+                    length
+                )
+            }
+            ExtrapolationError::TooManySteps { steps } => {
+                write!(
+                    f,
+                    "cannot extrapolate {} steps, at most {} at once",
+                    steps, MAX_STEPS
+                )
+            }
+        }
+    }
+}
+
+impl Error for ExtrapolationError {}
 
 fn parse_input(contents: String) -> Vec<Vec<Z>> {
     contents
@@ -13,36 +50,120 @@ fn parse_input(contents: String) -> Vec<Vec<Z>> {
         .collect()
 }
 
-fn derive(values: &Vec<Z>) -> Vec<Z> {
-    let mut tail = values.iter();
-    tail.next();
-
-    values.iter().zip(tail).map(|(a, b)| b - a).collect()
+fn derive(values: &[Z]) -> Result<Vec<Z>, ExtrapolationError> {
+    values
+        .windows(2)
+        .map(|pair| {
+            pair[1]
+                .checked_sub(pair[0])
+                .ok_or(ExtrapolationError::Overflow)
+        })
+        .collect()
 }
 
-fn all_zero(values: &Vec<Z>) -> bool {
+fn all_zero(values: &[Z]) -> bool {
     values.iter().all(|v| v == &0)
 }
 
-fn derives(values: &Vec<Z>) -> Vec<Vec<Z>> {
+fn derives(values: &[Z]) -> Result<Vec<Vec<Z>>, ExtrapolationError> {
     let mut ret = Vec::new();
 
-    let mut current_values = values.clone();
+    let mut current_values = values.to_vec();
     while !all_zero(&current_values) {
-        let next_values = derive(&current_values);
+        let next_values = derive(&current_values)?;
         ret.push(current_values);
         current_values = next_values;
     }
     ret.push(current_values);
 
-    return ret;
+    Ok(ret)
 }
 
-fn extrapolate_last(derives: &Vec<Vec<Z>>) -> Z {
-    derives
-        .iter()
-        .map(|values| values.last().unwrap_or(&0))
-        .sum()
+/*
+ * The integer valued polynomial through a sequence in Newton form:
+ * p(x) = Σ coefficients[k] * binomial(x, k) with the leading forward differences as coefficients.
+ * Position 0 is the first value of the sequence.
+ */
+#[derive(Debug, PartialEq)]
+struct NewtonPolynomial {
+    coefficients: Vec<Z>,
+    length: usize,
+}
+
+impl NewtonPolynomial {
+    fn fit(values: &[Z]) -> Result<NewtonPolynomial, ExtrapolationError> {
+        let mut derives = derives(values)?;
+
+        // A polynomial is only confirmed by a row of differences that is all zero and not empty
+        if derives.pop().is_none_or(|zeros| zeros.is_empty()) {
+            return Err(ExtrapolationError::NotPolynomial {
+                length: values.len(),
+            });
+        }
+
+        Ok(NewtonPolynomial {
+            coefficients: derives.iter().map(|values| values[0]).collect(),
+            length: values.len(),
+        })
+    }
+
+    // The zero polynomial is reported as degree 0
+    fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    fn evaluate(&self, position: Z) -> Result<Z, ExtrapolationError> {
+        let position = i128::from(position);
+        let mut binomial: i128 = 1;
+        let mut sum: i128 = 0;
+
+        for (k, coefficient) in (0..).zip(self.coefficients.iter()) {
+            if k > 0 {
+                // binomial(x, k) = binomial(x, k - 1) * (x - k + 1) / k, which divides exactly
+                binomial = binomial
+                    .checked_mul(position - k + 1)
+                    .ok_or(ExtrapolationError::Overflow)?
+                    / k;
+            }
+
+            let term = binomial
+                .checked_mul(i128::from(*coefficient))
+                .ok_or(ExtrapolationError::Overflow)?;
+            sum = sum.checked_add(term).ok_or(ExtrapolationError::Overflow)?;
+        }
+
+        Z::try_from(sum).map_err(|_| ExtrapolationError::Overflow)
+    }
+
+    // Positive steps continue after the last value, negative steps go back before the first value.
+    // The values are returned nearest to the sequence first.
+    fn extrapolate(&self, steps: i64) -> Result<Vec<Z>, ExtrapolationError> {
+        let length = Z::try_from(self.length).map_err(|_| ExtrapolationError::Overflow)?;
+        if steps.unsigned_abs() > MAX_STEPS {
+            return Err(ExtrapolationError::TooManySteps { steps });
+        }
+
+        if steps >= 0 {
+            let end = length
+                .checked_add(steps)
+                .ok_or(ExtrapolationError::Overflow)?;
+
+            (length..end)
+                .map(|position| self.evaluate(position))
+                .collect()
+        } else {
+            (steps..0)
+                .rev()
+                .map(|position| self.evaluate(position))
+                .collect()
+        }
+    }
+}
+
+fn extrapolate_last(values: &[Z]) -> Result<Z, ExtrapolationError> {
+    let next = NewtonPolynomial::fit(values)?.extrapolate(1)?;
+
+    Ok(next[0])
 }
 
 fn first() -> Result<(), Box<dyn Error>> {
@@ -54,8 +175,8 @@ fn first() -> Result<(), Box<dyn Error>> {
 
         let sum = input
             .iter()
-            .map(|values| extrapolate_last(&derives(values)))
-            .sum::<Z>();
+            .map(|values| extrapolate_last(values))
+            .sum::<Result<Z, _>>()?;
 
         println!("Sum: {}", sum);
     }
@@ -63,14 +184,8 @@ fn first() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn extrapolate_first(derives: &Vec<Vec<Z>>) -> Z {
-    let mut firsts = derives
-        .iter()
-        .map(|values| values.first().unwrap_or(&0))
-        .collect::<Vec<_>>();
-    firsts.reverse();
-
-    firsts.iter().fold(0, |acc, value| -> Z { **value - acc })
+fn extrapolate_first(values: &[Z]) -> Result<Z, ExtrapolationError> {
+    NewtonPolynomial::fit(values)?.evaluate(-1)
 }
 
 fn second() -> Result<(), Box<dyn Error>> {
@@ -82,8 +197,8 @@ fn second() -> Result<(), Box<dyn Error>> {
 
         let sum = input
             .iter()
-            .map(|values| extrapolate_first(&derives(values)))
-            .sum::<Z>();
+            .map(|values| extrapolate_first(values))
+            .sum::<Result<Z, _>>()?;
 
         println!("Sum: {}", sum);
     }
@@ -91,7 +206,105 @@ fn second() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+// The triangle of differences as drawn in the puzzle, extended by one extrapolated value on each end
+fn render_pyramid(values: &[Z]) -> Result<String, ExtrapolationError> {
+    NewtonPolynomial::fit(values)?;
+
+    let mut rows = derives(values)?;
+    let (mut below_first, mut below_last) = (0, 0);
+    for row in rows.iter_mut().rev() {
+        let first = row[0]
+            .checked_sub(below_first)
+            .ok_or(ExtrapolationError::Overflow)?;
+        let last = row[row.len() - 1]
+            .checked_add(below_last)
+            .ok_or(ExtrapolationError::Overflow)?;
+
+        row.insert(0, first);
+        row.push(last);
+        (below_first, below_last) = (first, last);
+    }
+
+    let width = rows
+        .iter()
+        .flatten()
+        .map(|value| value.to_string().len())
+        .max()
+        .unwrap_or(1);
+    // Cells have an even width so every row can be shifted by half a cell
+    let cell = (width + 2).next_multiple_of(2);
+
+    let lines = rows
+        .iter()
+        .enumerate()
+        .map(|(depth, row)| {
+            let cells = row
+                .iter()
+                .map(|value| format!("{:>cell$}", value))
+                .collect::<String>();
+
+            format!("{}{}", " ".repeat(depth * cell / 2), cells)
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+
+    Ok(lines.join("\n"))
+}
+
+fn history(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let paths = ["./inputs/09/example-1.txt", "./inputs/09/input.txt"];
+    let usage = "Usage: 9 degree | extrapolate <line> <steps> | evaluate <line> <position> \
+        | pyramid <line>";
+
+    for path in paths {
+        let contents = fs::read_to_string(path)?;
+        let input = parse_input(contents);
+
+        // Lines are numbered from 1 as in an editor
+        let line = |number: &String| -> Result<&Vec<Z>, Box<dyn Error>> {
+            let number = number.parse::<usize>()?;
+            let line = number.checked_sub(1).and_then(|index| input.get(index));
+
+            Ok(line.ok_or(format!("{} has no line {}", path, number))?)
+        };
+
+        println!("{}:", path);
+        match args {
+            [command] if command == "degree" => {
+                for (index, values) in input.iter().enumerate() {
+                    match NewtonPolynomial::fit(values) {
+                        Ok(polynomial) => println!("{}: degree {}", index + 1, polynomial.degree()),
+                        Err(error) => println!("{}: {}", index + 1, error),
+                    }
+                }
+            }
+            [command, number, steps] if command == "extrapolate" => {
+                let polynomial = NewtonPolynomial::fit(line(number)?)?;
+
+                println!("{:?}", polynomial.extrapolate(steps.parse()?)?);
+            }
+            [command, number, position] if command == "evaluate" => {
+                let polynomial = NewtonPolynomial::fit(line(number)?)?;
+
+                println!("{}", polynomial.evaluate(position.parse()?)?);
+            }
+            [command, number] if command == "pyramid" => {
+                println!("{}", render_pyramid(line(number)?)?);
+            }
+            _ => return Err(usage.into()),
+        }
+    }
+
+    Ok(())
+}
+
 pub fn main() -> Result<(), Box<dyn Error>> {
+    let args = crate::task_args();
+    if !args.is_empty() {
+        return history(&args);
+    }
+
     println!("09-1:");
     first()?;
     println!("09-2:");
@@ -102,7 +315,10 @@ pub fn main() -> Result<(), Box<dyn Error>> {
 
 #[cfg(test)]
 mod tests {
-    use super::{derives, extrapolate_first};
+    use super::{
+        derives, extrapolate_first, render_pyramid, ExtrapolationError, NewtonPolynomial,
+        MAX_STEPS, Z,
+    };
 
     #[test]
     fn derives_should_match_examples() {
@@ -137,7 +353,10 @@ mod tests {
             .to_vec(),
         ];
 
-        let actual_derives = examples.iter().map(derives).collect::<Vec<_>>();
+        let actual_derives = examples
+            .iter()
+            .map(|example| derives(example).unwrap())
+            .collect::<Vec<_>>();
         let [actual_1, actual_2, actual_3] = actual_derives.as_slice() else {
             todo!("test could not match actual outputs as expected")
         };
@@ -160,11 +379,95 @@ mod tests {
 
         let actual = examples
             .iter()
-            .map(|example| extrapolate_first(&derives(example)))
+            .map(|example| extrapolate_first(example).unwrap())
             .collect::<Vec<_>>();
 
         println!("Got actuals: {:?}", actual);
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn newton_polynomial_should_extrapolate_in_both_directions() {
+        let polynomial = NewtonPolynomial::fit(&[10, 13, 16, 21, 30, 45]).unwrap();
+
+        assert_eq!(polynomial.degree(), 3);
+        assert_eq!(polynomial.extrapolate(2), Ok([68, 101].to_vec()));
+        assert_eq!(polynomial.extrapolate(-2), Ok([5, -4].to_vec()));
+        assert_eq!(polynomial.extrapolate(0), Ok(Vec::new()));
+
+        // Evaluating at the known positions reproduces the sequence
+        let values = (0..6)
+            .map(|position| polynomial.evaluate(position).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(values, [10, 13, 16, 21, 30, 45].to_vec());
+    }
+
+    #[test]
+    fn newton_polynomial_should_match_a_cubic_far_away() {
+        let cubic = |x: Z| 2 * x * x * x - 7 * x * x + x - 4;
+        let values = (0..8).map(cubic).collect::<Vec<_>>();
+        let polynomial = NewtonPolynomial::fit(&values).unwrap();
+
+        for position in [-1000, -3, 8, 12345, 100_000] {
+            assert_eq!(polynomial.evaluate(position), Ok(cubic(position)));
+        }
+    }
+
+    #[test]
+    fn newton_polynomial_should_report_errors() {
+        assert_eq!(
+            NewtonPolynomial::fit(&[1, 2, 4, 8]),
+            Err(ExtrapolationError::NotPolynomial { length: 4 })
+        );
+        assert_eq!(
+            NewtonPolynomial::fit(&[]),
+            Err(ExtrapolationError::NotPolynomial { length: 0 })
+        );
+        assert_eq!(
+            NewtonPolynomial::fit(&[Z::MIN, Z::MAX, Z::MAX]),
+            Err(ExtrapolationError::Overflow)
+        );
+
+        let polynomial = NewtonPolynomial::fit(&[0, 1, 4, 9]).unwrap();
+        assert_eq!(
+            polynomial.evaluate(3_000_000_000),
+            Ok(9_000_000_000_000_000_000)
+        );
+        assert_eq!(
+            polynomial.evaluate(Z::MAX),
+            Err(ExtrapolationError::Overflow)
+        );
+
+        for steps in [Z::MAX, Z::MIN, MAX_STEPS as Z + 1] {
+            assert_eq!(
+                polynomial.extrapolate(steps),
+                Err(ExtrapolationError::TooManySteps { steps })
+            );
+        }
+        assert_eq!(
+            polynomial
+                .extrapolate(MAX_STEPS as Z)
+                .map(|values| values.len()),
+            Ok(MAX_STEPS as usize)
+        );
+    }
+
+    #[test]
+    fn render_pyramid_should_draw_the_extended_triangle() {
+        let expected = [
+            "   5  10  13  16  21  30  45  68",
+            "     5   3   3   5   9  15  23",
+            "      -2   0   2   4   6   8",
+            "         2   2   2   2   2",
+            "           0   0   0   0",
+        ]
+        .join("\n");
+
+        assert_eq!(render_pyramid(&[10, 13, 16, 21, 30, 45]), Ok(expected));
+        assert_eq!(
+            render_pyramid(&[1, 2, 4]),
+            Err(ExtrapolationError::NotPolynomial { length: 3 })
+        );
+    }
 }
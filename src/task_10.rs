@@ -1,4 +1,9 @@
-use std::{collections::HashMap, error::Error, fs};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt, fs,
+    path::Path,
+};
 
 type Coordinate = (i32, i32);
 type Input = HashMap<Coordinate, char>;
@@ -28,6 +33,198 @@ fn find_start(input: &Input) -> Coordinate {
     todo!("Unreachable by problem definition.");
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    fn opposite(self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+
+    fn step(self, (x, y): Coordinate) -> Coordinate {
+        match self {
+            Direction::North => (x, y - 1),
+            Direction::East => (x + 1, y),
+            Direction::South => (x, y + 1),
+            Direction::West => (x - 1, y),
+        }
+    }
+}
+
+// The two directions a pipe connects, in the order of Direction::ALL
+fn pipe_directions(symbol: char) -> Option<[Direction; 2]> {
+    match symbol {
+        '|' => Some([Direction::North, Direction::South]),
+        '-' => Some([Direction::East, Direction::West]),
+        'L' => Some([Direction::North, Direction::East]),
+        'J' => Some([Direction::North, Direction::West]),
+        '7' => Some([Direction::South, Direction::West]),
+        'F' => Some([Direction::East, Direction::South]),
+        _ => None,
+    }
+}
+
+fn pipe_symbol(directions: [Direction; 2]) -> char {
+    ['|', '-', 'L', 'J', '7', 'F']
+        .into_iter()
+        .find(|symbol| pipe_directions(*symbol) == Some(directions))
+        .unwrap_or('.')
+}
+
+#[derive(Debug, PartialEq)]
+enum LoopError {
+    NoStart,
+    SeveralStarts(Vec<Coordinate>),
+    // Only a start with exactly two connecting neighbours can be on a single loop
+    StartConnections {
+        start: Coordinate,
+        neighbours: Vec<Coordinate>,
+    },
+    // The pipe leaving the tile in the direction does not lead into another pipe
+    DeadEnd {
+        at: Coordinate,
+        direction: Direction,
+    },
+}
+
+impl fmt::Display for LoopError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoopError::NoStart => write!(f, "there is no start tile"),
+            LoopError::SeveralStarts(starts) => {
+                write!(f, "there are several start tiles at {:?}", starts)
+            }
+            LoopError::StartConnections { start, neighbours } if neighbours.len() < 2 => write!(
+                f,
+                "the start at {:?} is a dead end connecting only to {:?}",
+                start, neighbours
+            ),
+            LoopError::StartConnections { start, neighbours } => {
+                write!(f, "the start at {:?} branches into {:?}", start, neighbours)
+            }
+            LoopError::DeadEnd { at, direction } => {
+                write!(f, "the loop is broken going {:?} from {:?}", direction, at)
+            }
+        }
+    }
+}
+
+impl Error for LoopError {}
+
+// A tile of the loop and the direction in which the loop leaves it
+#[derive(Debug, PartialEq)]
+struct LoopStep {
+    coordinate: Coordinate,
+    direction: Direction,
+}
+
+#[derive(Debug, PartialEq)]
+struct PipeLoop {
+    // The pipe hidden under the start tile
+    start_tile: char,
+    // Starting at the start tile and following the loop
+    steps: Vec<LoopStep>,
+}
+
+impl PipeLoop {
+    fn coordinates(&self) -> Vec<Coordinate> {
+        self.steps.iter().map(|step| step.coordinate).collect()
+    }
+}
+
+fn extract_loop(input: &Input) -> Result<PipeLoop, LoopError> {
+    let mut starts = input
+        .iter()
+        .filter(|(_, symbol)| **symbol == 'S')
+        .map(|(coordinate, _)| *coordinate)
+        .collect::<Vec<_>>();
+    starts.sort();
+
+    let start = match starts.as_slice() {
+        [] => return Err(LoopError::NoStart),
+        [start] => *start,
+        _ => return Err(LoopError::SeveralStarts(starts)),
+    };
+
+    let connects = |coordinate: Coordinate, direction: Direction| {
+        input
+            .get(&coordinate)
+            .and_then(|symbol| pipe_directions(*symbol))
+            .is_some_and(|directions| directions.contains(&direction))
+    };
+
+    let exits = Direction::ALL
+        .into_iter()
+        .filter(|direction| connects(direction.step(start), direction.opposite()))
+        .collect::<Vec<_>>();
+    let [first_exit, last_exit] = exits.as_slice() else {
+        return Err(LoopError::StartConnections {
+            start,
+            neighbours: exits
+                .iter()
+                .map(|direction| direction.step(start))
+                .collect(),
+        });
+    };
+
+    let mut steps = Vec::new();
+    let mut coordinate = start;
+    let mut direction = *first_exit;
+
+    loop {
+        steps.push(LoopStep {
+            coordinate,
+            direction,
+        });
+
+        let next = direction.step(coordinate);
+        if next == start {
+            break;
+        }
+
+        let entry = direction.opposite();
+        let Some(directions) = input
+            .get(&next)
+            .and_then(|symbol| pipe_directions(*symbol))
+            .filter(|directions| directions.contains(&entry))
+        else {
+            return Err(LoopError::DeadEnd {
+                at: coordinate,
+                direction,
+            });
+        };
+
+        coordinate = next;
+        direction = if directions[0] == entry {
+            directions[1]
+        } else {
+            directions[0]
+        };
+    }
+
+    Ok(PipeLoop {
+        start_tile: pipe_symbol([*first_exit, *last_exit]),
+        steps,
+    })
+}
+
 // One sided transition possibility, may return invalid coordinates.
 fn reachable(input: &Input, from: &Coordinate) -> Vec<Coordinate> {
     let symbol = input.get(from).unwrap_or(&'.');
@@ -106,28 +303,9 @@ fn first() -> Result<(), Box<dyn Error>> {
 }
 
 fn replace_start(input: &Input) -> Input {
-    let start = find_start(input);
-    let nexts = connected(input, &start);
-
-    let different_ys = nexts.iter().filter(|n| n.1 != start.1).count();
-    let has_x_right = nexts.iter().any(|n| n.0 > start.0);
-    let has_y_bottom = nexts.iter().any(|n| n.1 > start.1);
-
-    let replacement = if different_ys >= 2 {
-        '|'
-    } else if different_ys > 0 {
-        if has_x_right {
-            if has_y_bottom {
-                'F'
-            } else {
-                'L'
-            }
-        } else {
-            '.'
-        }
-    } else {
-        '.'
-    };
+    let replacement = extract_loop(input)
+        .map(|pipe_loop| pipe_loop.start_tile)
+        .unwrap_or('.');
 
     input
         .iter()
@@ -196,6 +374,128 @@ fn count_insides(input: &Input, steps_to: &StepsTo) -> i32 {
     inside_count
 }
 
+// Twice the area enclosed by the polygon through the given vertices
+fn shoelace_double_area(vertices: &[Coordinate]) -> i64 {
+    let next_vertices = vertices.iter().cycle().skip(1);
+
+    vertices
+        .iter()
+        .zip(next_vertices)
+        .map(|((x1, y1), (x2, y2))| {
+            i64::from(*x1) * i64::from(*y2) - i64::from(*x2) * i64::from(*y1)
+        })
+        .sum::<i64>()
+        .abs()
+}
+
+/*
+ * Pick's theorem relates the area A of a lattice polygon to its interior points i and boundary points b:
+ * A = i + b / 2 - 1, so i = A - b / 2 + 1 = (2A - b + 2) / 2.
+ * The tiles of the loop are exactly the boundary points.
+ */
+fn count_insides_by_area(loop_tiles: &[Coordinate]) -> i64 {
+    let boundary = i64::try_from(loop_tiles.len()).unwrap();
+
+    (shoelace_double_area(loop_tiles) - boundary + 2) / 2
+}
+
+// Tiles enclosed by the loop: crossing a loop tile with a northward pipe flips between outside and inside
+fn inside_tiles(input: &Input, pipe_loop: &PipeLoop) -> HashSet<Coordinate> {
+    let loop_tiles = pipe_loop.coordinates().into_iter().collect::<HashSet<_>>();
+    let x_max = input.keys().map(|(x, _)| *x).max().unwrap_or(0);
+    let y_max = input.keys().map(|(_, y)| *y).max().unwrap_or(0);
+
+    let mut insides = HashSet::new();
+    for y in 0..=y_max {
+        let mut is_inside = false;
+
+        for x in 0..=x_max {
+            let coordinate = (x, y);
+
+            if loop_tiles.contains(&coordinate) {
+                let symbol = match input.get(&coordinate) {
+                    Some('S') => pipe_loop.start_tile,
+                    symbol => *symbol.unwrap_or(&'.'),
+                };
+
+                if pipe_directions(symbol).is_some_and(|d| d.contains(&Direction::North)) {
+                    is_inside = !is_inside;
+                }
+            } else if is_inside {
+                insides.insert(coordinate);
+            }
+        }
+    }
+
+    insides
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum RenderFormat {
+    // Colours for the terminal
+    Ansi,
+    // Heavy pipes for the loop and shade for inside tiles, for files
+    Plain,
+}
+
+fn box_drawing(symbol: char, heavy: bool) -> char {
+    match (symbol, heavy) {
+        ('|', false) => '│',
+        ('-', false) => '─',
+        ('L', false) => '└',
+        ('J', false) => '┘',
+        ('7', false) => '┐',
+        ('F', false) => '┌',
+        ('|', true) => '┃',
+        ('-', true) => '━',
+        ('L', true) => '┗',
+        ('J', true) => '┛',
+        ('7', true) => '┓',
+        ('F', true) => '┏',
+        _ => ' ',
+    }
+}
+
+fn render(input: &Input, pipe_loop: &PipeLoop, format: RenderFormat) -> String {
+    let loop_tiles = pipe_loop.coordinates().into_iter().collect::<HashSet<_>>();
+    let insides = inside_tiles(input, pipe_loop);
+    let x_max = input.keys().map(|(x, _)| *x).max().unwrap_or(0);
+    let y_max = input.keys().map(|(_, y)| *y).max().unwrap_or(0);
+
+    (0..=y_max)
+        .map(|y| {
+            (0..=x_max)
+                .map(|x| {
+                    let coordinate = (x, y);
+                    let symbol = match input.get(&coordinate) {
+                        Some('S') => pipe_loop.start_tile,
+                        symbol => *symbol.unwrap_or(&'.'),
+                    };
+                    let on_loop = loop_tiles.contains(&coordinate);
+                    let inside = insides.contains(&coordinate);
+
+                    match format {
+                        RenderFormat::Ansi => {
+                            let c = box_drawing(symbol, false);
+
+                            if on_loop {
+                                format!("\x1b[1;33m{}\x1b[0m", c)
+                            } else if inside {
+                                format!("\x1b[2;42m{}\x1b[0m", c)
+                            } else {
+                                format!("\x1b[2m{}\x1b[0m", c)
+                            }
+                        }
+                        RenderFormat::Plain if inside => '▒'.to_string(),
+                        RenderFormat::Plain => box_drawing(symbol, on_loop).to_string(),
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 fn second() -> Result<(), Box<dyn Error>> {
     let paths = [
         "./inputs/10/example-3.txt",
@@ -212,14 +512,59 @@ fn second() -> Result<(), Box<dyn Error>> {
         let steps_to = flood_fill(&input, &start);
 
         let insides = count_insides(&input, &steps_to);
+        let insides_by_area = count_insides_by_area(&extract_loop(&input)?.coordinates());
 
         println!("Insides counted are {}", insides);
+        println!("Insides by shoelace and Pick are {}", insides_by_area);
+    }
+
+    Ok(())
+}
+
+fn draw(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let paths = [
+        "./inputs/10/example-1.txt",
+        "./inputs/10/example-2.txt",
+        "./inputs/10/example-3.txt",
+        "./inputs/10/example-4.txt",
+        "./inputs/10/example-5.txt",
+        "./inputs/10/input.txt",
+    ];
+    let usage = "Usage: 10 render [<output directory>]";
+
+    for path in paths {
+        let input = parse_input(fs::read_to_string(path)?);
+        let pipe_loop = extract_loop(&input)?;
+
+        match args {
+            [command] if command == "render" => {
+                println!("{}:", path);
+                println!("{}", render(&input, &pipe_loop, RenderFormat::Ansi));
+            }
+            [command, directory] if command == "render" => {
+                let file_name = Path::new(path).with_extension("render.txt");
+                let file_name = file_name.file_name().ok_or(usage)?;
+                let output = Path::new(directory).join(file_name);
+
+                fs::write(
+                    &output,
+                    render(&input, &pipe_loop, RenderFormat::Plain) + "\n",
+                )?;
+                println!("Wrote {}", output.display());
+            }
+            _ => return Err(usage.into()),
+        }
     }
 
     Ok(())
 }
 
 pub fn main() -> Result<(), Box<dyn Error>> {
+    let args = crate::task_args();
+    if !args.is_empty() {
+        return draw(&args);
+    }
+
     println!("10-1:");
     first()?;
     println!("10-2:");
@@ -227,3 +572,162 @@ pub fn main() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, fs};
+
+    use super::{
+        count_insides, count_insides_by_area, extract_loop, find_start, flood_fill, inside_tiles,
+        parse_input, render, replace_start, Direction, LoopError, LoopStep, RenderFormat,
+    };
+
+    #[test]
+    fn count_insides_by_area_should_agree_with_scanline() -> Result<(), Box<dyn Error>> {
+        let expected = [1, 1, 4, 8, 10];
+
+        for (index, expected) in expected.into_iter().enumerate() {
+            let path = format!("./inputs/10/example-{}.txt", index + 1);
+            let input = parse_input(fs::read_to_string(&path)?);
+
+            let start = find_start(&input);
+            let steps_to = flood_fill(&input, &start);
+            let loop_tiles = extract_loop(&input)?.coordinates();
+
+            assert_eq!(loop_tiles.len(), steps_to.len(), "{}", path);
+            assert_eq!(count_insides_by_area(&loop_tiles), expected, "{}", path);
+            assert_eq!(
+                inside_tiles(&input, &extract_loop(&input)?).len(),
+                expected as usize,
+                "{}",
+                path
+            );
+            assert_eq!(
+                i64::from(count_insides(&input, &steps_to)),
+                expected,
+                "{}",
+                path
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn extract_loop_should_follow_the_pipes_in_order() -> Result<(), Box<dyn Error>> {
+        let input = parse_input(fs::read_to_string("./inputs/10/example-2.txt")?);
+        let pipe_loop = extract_loop(&input)?;
+
+        assert_eq!(pipe_loop.start_tile, 'F');
+        assert_eq!(pipe_loop.steps.len(), 16);
+        assert_eq!(
+            pipe_loop.steps[..3],
+            [
+                LoopStep {
+                    coordinate: (0, 2),
+                    direction: Direction::East,
+                },
+                LoopStep {
+                    coordinate: (1, 2),
+                    direction: Direction::North,
+                },
+                LoopStep {
+                    coordinate: (1, 1),
+                    direction: Direction::East,
+                },
+            ]
+        );
+
+        // Every step leads to the tile of the next step, and the last one back to the start
+        for (step, next) in pipe_loop
+            .steps
+            .iter()
+            .zip(pipe_loop.steps.iter().cycle().skip(1))
+        {
+            assert_eq!(step.direction.step(step.coordinate), next.coordinate);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn extract_loop_should_infer_every_start_tile() {
+        let cases = [
+            ([".....", ".F-7.", ".|.|.", ".L-S.", "....."], 'J'),
+            ([".....", ".S-7.", ".|.|.", ".L-J.", "....."], 'F'),
+            ([".....", ".F-S.", ".|.|.", ".L-J.", "....."], '7'),
+            ([".....", ".F-7.", ".S.|.", ".L-J.", "....."], '|'),
+            ([".....", ".F-7.", ".|.|.", ".S-J.", "....."], 'L'),
+            ([".....", ".FS7.", ".|.|.", ".L-J.", "....."], '-'),
+        ];
+
+        for (lines, expected) in cases {
+            let input = parse_input(lines.join("\n"));
+
+            assert_eq!(
+                extract_loop(&input).map(|pipe_loop| pipe_loop.start_tile),
+                Ok(expected)
+            );
+            assert!(replace_start(&input).values().all(|symbol| *symbol != 'S'));
+        }
+    }
+
+    #[test]
+    fn extract_loop_should_report_broken_loops() {
+        let dead_end = parse_input([".....", ".S-7.", ".|.|.", ".L-..", "....."].join("\n"));
+        assert_eq!(
+            extract_loop(&dead_end),
+            Err(LoopError::DeadEnd {
+                at: (3, 2),
+                direction: Direction::South,
+            })
+        );
+
+        let branch = parse_input([".....", ".F-7.", "-S.|.", ".L-J.", "....."].join("\n"));
+        assert_eq!(
+            extract_loop(&branch),
+            Err(LoopError::StartConnections {
+                start: (1, 2),
+                neighbours: [(1, 1), (1, 3), (0, 2)].to_vec(),
+            })
+        );
+
+        let lonely = parse_input(["...", ".S.", "..."].join("\n"));
+        assert_eq!(
+            extract_loop(&lonely),
+            Err(LoopError::StartConnections {
+                start: (1, 1),
+                neighbours: Vec::new(),
+            })
+        );
+
+        assert_eq!(
+            extract_loop(&parse_input("...".to_string())),
+            Err(LoopError::NoStart)
+        );
+    }
+
+    #[test]
+    fn render_should_redraw_the_maze_with_box_drawing() -> Result<(), Box<dyn Error>> {
+        let input = parse_input(fs::read_to_string("./inputs/10/example-1.txt")?);
+        let pipe_loop = extract_loop(&input)?;
+
+        let expected = [
+            "─└│┌┐", //
+            "┐┏━┓│",
+            "└┃▒┃│",
+            "─┗━┛│",
+            "└│─┘┌",
+        ]
+        .join("\n");
+
+        assert_eq!(render(&input, &pipe_loop, RenderFormat::Plain), expected);
+
+        let ansi = render(&input, &pipe_loop, RenderFormat::Ansi);
+        assert!(ansi.contains("\x1b[1;33m┌\x1b[0m"));
+        assert!(ansi.contains("\x1b[2;42m┐\x1b[0m"));
+        assert!(ansi.contains("\x1b[2m└\x1b[0m"));
+
+        Ok(())
+    }
+}
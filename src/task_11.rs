@@ -1,5 +1,5 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap},
     error::Error,
     fs,
 };
@@ -24,74 +24,348 @@ fn parse_input(contents: String) -> InputMap {
         .collect::<InputMap>()
 }
 
+// Galaxies in reading order, so they are numbered as in the puzzle
 fn find_galaxies(input: &InputMap) -> Vec<Coordinate> {
-    input
+    let mut galaxies = input
         .iter()
         .filter(|(_, c)| **c == '#')
         .map(|((x, y), _)| -> Coordinate { (*x, *y) })
-        .collect::<Vec<_>>()
+        .collect::<Vec<_>>();
+    galaxies.sort_by_key(|(x, y)| (*y, *x));
+
+    galaxies
 }
 
-fn find_dimensions(galaxies: &Vec<Coordinate>) -> (N, N) {
-    let x = galaxies.iter().map(|(x, _)| x).max().unwrap_or(&0);
-    let y = galaxies.iter().map(|(_, y)| y).max().unwrap_or(&0);
+// For every value the number of unoccupied lines from 0 up to it, counted by its rank among the occupied lines
+fn expansions(values: &[N]) -> Vec<N> {
+    let mut occupied = values.to_vec();
+    occupied.sort_unstable();
+    occupied.dedup();
 
-    (*x, *y)
-}
+    values
+        .iter()
+        .map(|value| {
+            let rank = occupied.partition_point(|occupied| occupied < value);
 
-fn expand_galaxies(galaxies: &Vec<Coordinate>, additional_distance: N) -> Vec<Coordinate> {
-    let galaxy_cols = galaxies.iter().map(|(x, _)| *x).collect::<HashSet<_>>();
-    let galaxy_rows = galaxies.iter().map(|(_, y)| *y).collect::<HashSet<_>>();
+            value - N::try_from(rank).unwrap()
+        })
+        .collect()
+}
 
-    let (x_max, y_max) = find_dimensions(galaxies);
+fn expand_galaxies(galaxies: &[Coordinate], additional_distance: N) -> Vec<Coordinate> {
+    expand_galaxies_per_axis(galaxies, additional_distance, additional_distance)
+}
 
-    let expand_cols = (0..x_max)
-        .filter(|x| !galaxy_cols.contains(x))
-        .collect::<HashSet<_>>();
-    let expand_rows = (0..y_max)
-        .filter(|y| !galaxy_rows.contains(y))
-        .collect::<HashSet<_>>();
+fn expand_galaxies_per_axis(
+    galaxies: &[Coordinate],
+    additional_x: N,
+    additional_y: N,
+) -> Vec<Coordinate> {
+    let xs = galaxies.iter().map(|(x, _)| *x).collect::<Vec<_>>();
+    let ys = galaxies.iter().map(|(_, y)| *y).collect::<Vec<_>>();
 
     galaxies
         .iter()
-        .map(|(x, y)| -> Coordinate {
-            let expand_x = expand_cols.iter().filter(|col| *col < x).count();
-            let expand_y = expand_rows.iter().filter(|row| *row < y).count();
-
-            let expand_x = N::try_from(expand_x).unwrap_or(0);
-            let expand_y = N::try_from(expand_y).unwrap_or(0);
-
-            (
-                x + expand_x * additional_distance,
-                y + expand_y * additional_distance,
-            )
+        .zip(expansions(&xs).into_iter().zip(expansions(&ys)))
+        .map(|((x, y), (expand_x, expand_y))| -> Coordinate {
+            (x + expand_x * additional_x, y + expand_y * additional_y)
         })
         .collect()
 }
 
-fn distance(a: &Coordinate, b: &Coordinate) -> N {
-    let (x1, y1) = a;
-    let (x2, y2) = b;
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Metric {
+    Manhattan,
+    Chebyshev,
+    EuclideanSquared,
+}
+
+impl Metric {
+    fn from_name(name: &str) -> Option<Metric> {
+        match name {
+            "manhattan" => Some(Metric::Manhattan),
+            "chebyshev" => Some(Metric::Chebyshev),
+            "euclidean-squared" => Some(Metric::EuclideanSquared),
+            _ => None,
+        }
+    }
+
+    fn distance(self, (x1, y1): &Coordinate, (x2, y2): &Coordinate) -> N {
+        let (dx, dy) = ((x1 - x2).abs(), (y1 - y2).abs());
+
+        match self {
+            Metric::Manhattan => dx + dy,
+            Metric::Chebyshev => dx.max(dy),
+            Metric::EuclideanSquared => dx * dx + dy * dy,
+        }
+    }
+}
+
+// Sum of |a - b| over all pairs: after sorting, each value is larger than all values before it
+fn axis_distance_sum(mut values: Vec<N>) -> N {
+    values.sort_unstable();
+
+    let mut prefix_sum = 0;
+    let mut sum = 0;
+    for (count, value) in (0..).zip(values) {
+        sum += value * count - prefix_sum;
+        prefix_sum += value;
+    }
+
+    sum
+}
+
+// Sum of (a - b)² over all pairs, which is n * Σa² - (Σa)²
+fn axis_squared_distance_sum(values: Vec<N>) -> N {
+    let count = N::try_from(values.len()).unwrap();
+    let sum = values.iter().sum::<N>();
+    let sum_of_squares = values.iter().map(|value| value * value).sum::<N>();
+
+    count * sum_of_squares - sum * sum
+}
+
+// The pairs are never visited one by one: Manhattan and squared Euclidean distances separate
+// into independent axes, and Chebyshev does so once the plane is rotated by 45°
+fn sum_of_distances(galaxies: &[Coordinate], metric: Metric) -> N {
+    match metric {
+        Metric::Manhattan => {
+            let xs = galaxies.iter().map(|(x, _)| *x).collect();
+            let ys = galaxies.iter().map(|(_, y)| *y).collect();
+
+            axis_distance_sum(xs) + axis_distance_sum(ys)
+        }
+        // Rotating by 45° turns Chebyshev into half the Manhattan distance
+        Metric::Chebyshev => {
+            let us = galaxies.iter().map(|(x, y)| x + y).collect();
+            let vs = galaxies.iter().map(|(x, y)| x - y).collect();
+
+            (axis_distance_sum(us) + axis_distance_sum(vs)) / 2
+        }
+        Metric::EuclideanSquared => {
+            let xs = galaxies.iter().map(|(x, _)| *x).collect();
+            let ys = galaxies.iter().map(|(_, y)| *y).collect();
+
+            axis_squared_distance_sum(xs) + axis_squared_distance_sum(ys)
+        }
+    }
+}
+
+// All pairs of galaxy indices together with their distance
+fn pair_distances(
+    galaxies: &[Coordinate],
+    metric: Metric,
+) -> impl Iterator<Item = (usize, usize, N)> + '_ {
+    galaxies.iter().enumerate().flat_map(move |(a, first)| {
+        galaxies
+            .iter()
+            .enumerate()
+            .skip(a + 1)
+            .map(move |(b, second)| (a, b, metric.distance(first, second)))
+    })
+}
 
-    (x1 - x2).abs() + (y1 - y2).abs()
+// Orders a pair of indices so the smaller one comes first
+fn ordered_pair(a: usize, b: usize, distance: N) -> (usize, usize, N) {
+    (a.min(b), a.max(b), distance)
 }
 
-fn distances(galaxies: &Vec<Coordinate>) -> Vec<N> {
-    let mut galaxies = galaxies.as_slice();
-    let mut distances: Vec<N> = Vec::new();
+/*
+ * Sweeps over the galaxies from left to right, keeping only those whose x distance alone
+ * does not exceed the best distance so far, ordered by y.
+ * The distance of the projections onto either axis never exceeds the full distance for any metric,
+ * so only the few galaxies close on both axes are compared.
+ * Ties are broken towards the lowest galaxy indices.
+ */
+fn nearest_pair(galaxies: &[Coordinate], metric: Metric) -> Option<(usize, usize, N)> {
+    let mut by_x = (0..galaxies.len()).collect::<Vec<_>>();
+    by_x.sort_by_key(|index| galaxies[*index]);
+
+    let x_distance = |a: usize, b: usize| metric.distance(&(galaxies[a].0, 0), &(galaxies[b].0, 0));
+    let y_distance = |a: usize, b: usize| metric.distance(&(0, galaxies[a].1), &(0, galaxies[b].1));
+    let within = |distance: N, best: Option<(usize, usize, N)>| {
+        best.is_none_or(|(_, _, best)| distance <= best)
+    };
+
+    let mut best: Option<(usize, usize, N)> = None;
+    let mut active = BTreeSet::new();
+    let mut oldest = 0;
+
+    for (position, current) in by_x.iter().copied().enumerate() {
+        while oldest < position && !within(x_distance(by_x[oldest], current), best) {
+            active.remove(&(galaxies[by_x[oldest]].1, by_x[oldest]));
+            oldest += 1;
+        }
+
+        let y = galaxies[current].1;
+        // Walk away from the current y in both directions until the y distance alone is too large
+        let sides: [Box<dyn Iterator<Item = &(N, usize)>>; 2] = [
+            Box::new(active.range(..(y, current)).rev()),
+            Box::new(active.range((y, current)..)),
+        ];
+        for side in sides {
+            for (_, other) in side {
+                if !within(y_distance(*other, current), best) {
+                    break;
+                }
+
+                let candidate = ordered_pair(
+                    *other,
+                    current,
+                    metric.distance(&galaxies[*other], &galaxies[current]),
+                );
+                if best.is_none_or(|(a, b, distance)| {
+                    (candidate.2, candidate.0, candidate.1) < (distance, a, b)
+                }) {
+                    best = Some(candidate);
+                }
+            }
+        }
+
+        active.insert((y, current));
+    }
 
-    while galaxies.len() > 1 {
-        let first = galaxies.first().unwrap();
-        let others = &galaxies[1..];
+    best
+}
 
-        for other in others {
-            distances.push(distance(first, other));
+// Indices of the galaxies with the lowest and highest value of the projection
+fn extremes(galaxies: &[Coordinate], projection: impl Fn(&Coordinate) -> N) -> (usize, usize) {
+    let (lowest, _) = galaxies
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, galaxy)| projection(galaxy))
+        .unwrap();
+    let (highest, _) = galaxies
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, galaxy)| projection(galaxy))
+        .unwrap();
+
+    (lowest, highest)
+}
+
+// Twice the signed area of the triangle, positive when a, b, c turn to the left
+fn cross((ax, ay): &Coordinate, (bx, by): &Coordinate, (cx, cy): &Coordinate) -> N {
+    (bx - ax) * (cy - ay) - (by - ay) * (cx - ax)
+}
+
+// Indices of the corners of the convex hull in counterclockwise order, by Andrew's monotone chain
+fn convex_hull(galaxies: &[Coordinate]) -> Vec<usize> {
+    let mut sorted = (0..galaxies.len()).collect::<Vec<_>>();
+    sorted.sort_by_key(|index| galaxies[*index]);
+    sorted.dedup_by_key(|index| galaxies[*index]);
+
+    let turns_left =
+        |a: usize, b: usize, c: usize| cross(&galaxies[a], &galaxies[b], &galaxies[c]) > 0;
+
+    let mut hull: Vec<usize> = Vec::new();
+    for pass in [sorted.clone(), sorted.into_iter().rev().collect()] {
+        let start = hull.len();
+        for index in pass {
+            while hull.len() >= start + 2
+                && !turns_left(hull[hull.len() - 2], hull[hull.len() - 1], index)
+            {
+                hull.pop();
+            }
+            hull.push(index);
         }
+        // The last corner of each chain starts the other one
+        hull.pop();
+    }
 
-        galaxies = others;
+    hull
+}
+
+/*
+ * Rotating calipers: for every edge of the hull the corner farthest from it is found
+ * by moving on from the one found for the previous edge, so the hull is walked around only twice.
+ */
+fn antipodal_pairs(galaxies: &[Coordinate], hull: &[usize]) -> Vec<(usize, usize)> {
+    if hull.len() < 3 {
+        return hull.iter().map(|a| (hull[0], *a)).collect();
+    }
+
+    let corner = |position: usize| &galaxies[hull[position % hull.len()]];
+    let mut pairs = Vec::new();
+    let mut opposite = 1;
+
+    for position in 0..hull.len() {
+        let (start, end) = (corner(position), corner(position + 1));
+        while cross(start, end, corner(opposite + 1)) > cross(start, end, corner(opposite)) {
+            opposite += 1;
+        }
+
+        // An edge parallel to this one has both of its corners at the same distance
+        for other in [opposite, opposite + 1] {
+            let other = hull[other % hull.len()];
+            pairs.push((hull[position], other));
+            pairs.push((hull[(position + 1) % hull.len()], other));
+        }
     }
 
-    return distances;
+    pairs
+}
+
+/*
+ * Manhattan distance is largest between the extremes of x + y or of x - y,
+ * and Chebyshev distance between the extremes of x or of y, so those need a single pass.
+ * The farthest squared Euclidean pair is among the antipodal pairs of the convex hull.
+ */
+fn farthest_pair(galaxies: &[Coordinate], metric: Metric) -> Option<(usize, usize, N)> {
+    if galaxies.len() < 2 {
+        return None;
+    }
+
+    let candidates = match metric {
+        Metric::Manhattan => [
+            extremes(galaxies, |(x, y)| x + y),
+            extremes(galaxies, |(x, y)| x - y),
+        ]
+        .to_vec(),
+        Metric::Chebyshev => [
+            extremes(galaxies, |(x, _)| *x),
+            extremes(galaxies, |(_, y)| *y),
+        ]
+        .to_vec(),
+        Metric::EuclideanSquared => antipodal_pairs(galaxies, &convex_hull(galaxies)),
+    };
+
+    candidates
+        .into_iter()
+        .filter(|(a, b)| a != b)
+        .map(|(a, b)| ordered_pair(a, b, metric.distance(&galaxies[a], &galaxies[b])))
+        .max_by_key(|(_, _, distance)| *distance)
+        // All galaxies share the same position
+        .or(Some((0, 1, 0)))
+}
+
+fn distance_between(galaxies: &[Coordinate], metric: Metric, a: usize, b: usize) -> Option<N> {
+    Some(metric.distance(galaxies.get(a)?, galaxies.get(b)?))
+}
+
+// Above this many galaxies there are too many pairs to count them one by one
+const HISTOGRAM_GALAXY_LIMIT: usize = 10_000;
+
+/*
+ * Number of pairs per bucket of distances, keyed by the lowest distance of the bucket.
+ * Every pair is visited, so this takes quadratic time and refuses more than HISTOGRAM_GALAXY_LIMIT galaxies.
+ */
+fn distance_histogram(
+    galaxies: &[Coordinate],
+    metric: Metric,
+    bucket_width: N,
+) -> Option<BTreeMap<N, usize>> {
+    if galaxies.len() > HISTOGRAM_GALAXY_LIMIT {
+        return None;
+    }
+
+    let mut histogram = BTreeMap::new();
+
+    for (_, _, distance) in pair_distances(galaxies, metric) {
+        let bucket = distance - distance.rem_euclid(bucket_width);
+        *histogram.entry(bucket).or_insert(0) += 1;
+    }
+
+    Some(histogram)
 }
 
 fn first() -> Result<(), Box<dyn Error>> {
@@ -105,7 +379,7 @@ fn first() -> Result<(), Box<dyn Error>> {
         let galaxies = find_galaxies(&input);
         let galaxies = expand_galaxies(&galaxies, 1);
 
-        let sum = distances(&galaxies).iter().sum::<N>();
+        let sum = sum_of_distances(&galaxies, Metric::Manhattan);
         println!("Sum of distances is {}", sum);
     }
 
@@ -123,14 +397,88 @@ fn second() -> Result<(), Box<dyn Error>> {
         let galaxies = find_galaxies(&input);
         let galaxies = expand_galaxies(&galaxies, 1000000 - 1);
 
-        let sum = distances(&galaxies).iter().sum::<N>();
+        let sum = sum_of_distances(&galaxies, Metric::Manhattan);
         println!("Sum of distances is {}", sum);
     }
 
     Ok(())
 }
 
+fn query(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let paths = ["./inputs/11/example-1.txt", "./inputs/11/input.txt"];
+    let usage = "Usage: 11 <sum|nearest|farthest> <metric> <factor x> <factor y> \
+        | distance <metric> <factor x> <factor y> <galaxy> <galaxy> \
+        | histogram <metric> <factor x> <factor y> <bucket width> \
+        (metrics: manhattan, chebyshev, euclidean-squared; galaxies are numbered from 1)";
+
+    let [command, metric, factor_x, factor_y, rest @ ..] = args else {
+        return Err(usage.into());
+    };
+    let metric = Metric::from_name(metric).ok_or(usage)?;
+    let (factor_x, factor_y) = (factor_x.parse::<N>()?, factor_y.parse::<N>()?);
+    if factor_x < 1 || factor_y < 1 {
+        return Err(usage.into());
+    }
+    let (additional_x, additional_y) = (factor_x - 1, factor_y - 1);
+
+    for path in paths {
+        println!("Handling file {}:", path);
+
+        let contents = fs::read_to_string(path)?;
+        let galaxies = find_galaxies(&parse_input(contents));
+        let galaxies = expand_galaxies_per_axis(&galaxies, additional_x, additional_y);
+
+        match (command.as_str(), rest) {
+            ("sum", []) => println!("{}", sum_of_distances(&galaxies, metric)),
+            ("nearest", []) | ("farthest", []) => {
+                let pair = if command == "nearest" {
+                    nearest_pair(&galaxies, metric)
+                } else {
+                    farthest_pair(&galaxies, metric)
+                };
+
+                match pair {
+                    Some((a, b, distance)) => {
+                        println!("Galaxies {} and {} at {}", a + 1, b + 1, distance)
+                    }
+                    None => println!("Fewer than two galaxies"),
+                }
+            }
+            ("distance", [a, b]) => {
+                let a = a.parse::<usize>()?.checked_sub(1).ok_or(usage)?;
+                let b = b.parse::<usize>()?.checked_sub(1).ok_or(usage)?;
+                let distance = distance_between(&galaxies, metric, a, b)
+                    .ok_or(format!("There are only {} galaxies", galaxies.len()))?;
+
+                println!("{}", distance);
+            }
+            ("histogram", [bucket_width]) => {
+                let bucket_width = bucket_width.parse::<N>()?;
+                if bucket_width <= 0 {
+                    return Err(usage.into());
+                }
+
+                let histogram = distance_histogram(&galaxies, metric, bucket_width).ok_or(
+                    format!("Cannot count the pairs of {} galaxies", galaxies.len()),
+                )?;
+
+                for (bucket, count) in histogram {
+                    println!("{}..{}: {}", bucket, bucket + bucket_width, count);
+                }
+            }
+            _ => return Err(usage.into()),
+        }
+    }
+
+    Ok(())
+}
+
 pub fn main() -> Result<(), Box<dyn Error>> {
+    let args = crate::task_args();
+    if !args.is_empty() {
+        return query(&args);
+    }
+
     println!("11-1:");
     first()?;
     println!("11-2:");
@@ -138,3 +486,227 @@ pub fn main() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeMap, HashSet},
+        error::Error,
+        fs,
+    };
+
+    use crate::random::Lcg;
+
+    use super::{
+        distance_between, distance_histogram, expand_galaxies, expand_galaxies_per_axis,
+        farthest_pair, find_galaxies, nearest_pair, pair_distances, parse_input, sum_of_distances,
+        Coordinate, Metric, HISTOGRAM_GALAXY_LIMIT, N,
+    };
+
+    // The straightforward expansion that checks every empty line for every galaxy
+    fn expand_naively(galaxies: &[Coordinate], additional_distance: N) -> Vec<Coordinate> {
+        let cols = galaxies.iter().map(|(x, _)| *x).collect::<HashSet<_>>();
+        let rows = galaxies.iter().map(|(_, y)| *y).collect::<HashSet<_>>();
+
+        galaxies
+            .iter()
+            .map(|(x, y)| {
+                let expand_x = (0..*x).filter(|col| !cols.contains(col)).count() as N;
+                let expand_y = (0..*y).filter(|row| !rows.contains(row)).count() as N;
+
+                (
+                    x + expand_x * additional_distance,
+                    y + expand_y * additional_distance,
+                )
+            })
+            .collect()
+    }
+
+    fn pairwise_sum(galaxies: &[Coordinate], metric: Metric) -> N {
+        pair_distances(galaxies, metric)
+            .map(|(_, _, distance)| distance)
+            .sum()
+    }
+
+    fn random_galaxies(count: usize, bound: u64, seed: u64) -> Vec<Coordinate> {
+        let mut random = Lcg::new(seed);
+
+        (0..count)
+            .map(|_| (N::from(random.below(bound)), N::from(random.below(bound))))
+            .collect()
+    }
+
+    #[test]
+    fn sum_of_distances_should_match_examples() -> Result<(), Box<dyn Error>> {
+        let contents = fs::read_to_string("./inputs/11/example-1.txt")?;
+        let galaxies = find_galaxies(&parse_input(contents));
+
+        for (additional_distance, expected) in [(1, 374), (9, 1030), (99, 8410)] {
+            let expanded = expand_galaxies(&galaxies, additional_distance);
+
+            assert_eq!(sum_of_distances(&expanded, Metric::Manhattan), expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn sum_of_distances_should_agree_with_pairwise_sums() {
+        for seed in 0..20 {
+            let galaxies = random_galaxies(60, 40, seed);
+
+            let expanded = expand_galaxies(&galaxies, 5);
+            assert_eq!(expanded, expand_naively(&galaxies, 5));
+            for metric in [
+                Metric::Manhattan,
+                Metric::Chebyshev,
+                Metric::EuclideanSquared,
+            ] {
+                assert_eq!(
+                    sum_of_distances(&expanded, metric),
+                    pairwise_sum(&expanded, metric)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn sum_of_distances_should_handle_many_galaxies() {
+        // Sparse enough that most rows and columns are expanded, so distances grow large
+        let galaxies = random_galaxies(2_000, 1_000_000, 11);
+        let expanded = expand_galaxies(&galaxies, 999_999);
+
+        for metric in [
+            Metric::Manhattan,
+            Metric::Chebyshev,
+            Metric::EuclideanSquared,
+        ] {
+            assert_eq!(
+                sum_of_distances(&expanded, metric),
+                pairwise_sum(&expanded, metric)
+            );
+        }
+        assert_eq!(sum_of_distances(&[], Metric::Chebyshev), 0);
+        assert_eq!(expand_galaxies(&[], 1), Vec::new());
+    }
+
+    #[test]
+    fn pair_queries_should_match_example() -> Result<(), Box<dyn Error>> {
+        let contents = fs::read_to_string("./inputs/11/example-1.txt")?;
+        let galaxies = expand_galaxies(&find_galaxies(&parse_input(contents)), 1);
+
+        // Galaxies are numbered from 1 in the puzzle
+        let manhattan =
+            |a: usize, b: usize| distance_between(&galaxies, Metric::Manhattan, a - 1, b - 1);
+        assert_eq!(manhattan(5, 9), Some(9));
+        assert_eq!(manhattan(1, 7), Some(15));
+        assert_eq!(manhattan(3, 6), Some(17));
+        assert_eq!(manhattan(8, 9), Some(5));
+        assert_eq!(manhattan(1, 10), None);
+
+        assert_eq!(nearest_pair(&galaxies, Metric::Manhattan), Some((1, 3, 5)));
+        assert_eq!(
+            farthest_pair(&galaxies, Metric::Manhattan).map(|(_, _, d)| d),
+            Some(19)
+        );
+        assert_eq!(nearest_pair(&galaxies[..1], Metric::Chebyshev), None);
+
+        assert_eq!(
+            distance_histogram(&galaxies, Metric::Manhattan, 5),
+            Some(BTreeMap::from([(5, 18), (10, 11), (15, 7)]))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn pair_queries_should_agree_with_all_pairs() {
+        for seed in 0..20 {
+            // Few distinct positions, so there are duplicates and ties
+            let galaxies = random_galaxies(80, 12, seed);
+
+            for metric in [
+                Metric::Manhattan,
+                Metric::Chebyshev,
+                Metric::EuclideanSquared,
+            ] {
+                let nearest = pair_distances(&galaxies, metric)
+                    .min_by_key(|(a, b, distance)| (*distance, *a, *b));
+                let farthest = pair_distances(&galaxies, metric)
+                    .map(|(_, _, distance)| distance)
+                    .max();
+
+                assert_eq!(nearest_pair(&galaxies, metric), nearest);
+
+                let (a, b, distance) = farthest_pair(&galaxies, metric).unwrap();
+                assert_eq!(Some(distance), farthest);
+                assert!(a < b);
+                assert_eq!(metric.distance(&galaxies[a], &galaxies[b]), distance);
+            }
+        }
+
+        let same = [(3, 4); 3];
+        assert_eq!(
+            farthest_pair(&same, Metric::EuclideanSquared),
+            Some((0, 1, 0))
+        );
+        assert_eq!(nearest_pair(&same, Metric::Manhattan), Some((0, 1, 0)));
+        assert_eq!(
+            farthest_pair(&[(1, 1), (2, 2), (3, 3)], Metric::EuclideanSquared),
+            Some((0, 2, 8))
+        );
+    }
+
+    #[test]
+    fn pair_queries_should_handle_many_galaxies() {
+        // A square lattice, in reading order like found galaxies
+        let side = 150;
+        let lattice = (0..side)
+            .flat_map(|y| (0..side).map(move |x| (x, y)))
+            .collect::<Vec<Coordinate>>();
+
+        for (metric, farthest) in [
+            (Metric::Manhattan, 2 * (side - 1)),
+            (Metric::Chebyshev, side - 1),
+            (Metric::EuclideanSquared, 2 * (side - 1) * (side - 1)),
+        ] {
+            assert_eq!(nearest_pair(&lattice, metric), Some((0, 1, 1)));
+            assert_eq!(
+                farthest_pair(&lattice, metric).map(|(_, _, distance)| distance),
+                Some(farthest)
+            );
+        }
+
+        // Every point of a parabola is a corner of the convex hull
+        let parabola = (0..2000).map(|x| (x, x * x)).collect::<Vec<Coordinate>>();
+        assert_eq!(
+            farthest_pair(&parabola, Metric::EuclideanSquared),
+            Some((0, 1999, 1999 * 1999 + 1999_i128.pow(4)))
+        );
+        assert_eq!(
+            nearest_pair(&parabola, Metric::EuclideanSquared),
+            Some((0, 1, 2))
+        );
+
+        assert_eq!(
+            distance_histogram(&lattice[..HISTOGRAM_GALAXY_LIMIT + 1], Metric::Manhattan, 5),
+            None
+        );
+    }
+
+    #[test]
+    fn expansion_should_apply_per_axis() {
+        let galaxies = [(0, 0), (2, 3)];
+
+        assert_eq!(
+            expand_galaxies_per_axis(&galaxies, 9, 0),
+            [(0, 0), (11, 3)].to_vec()
+        );
+        assert_eq!(
+            expand_galaxies_per_axis(&galaxies, 0, 9),
+            [(0, 0), (2, 21)].to_vec()
+        );
+        assert_eq!(Metric::Chebyshev.distance(&(0, 0), &(11, 3)), 11);
+        assert_eq!(Metric::EuclideanSquared.distance(&(0, 0), &(11, 3)), 130);
+    }
+}